@@ -0,0 +1,7 @@
+ALTER TABLE Rating DROP CONSTRAINT IF EXISTS UQ_Rating_Match_Username;
+
+INSERT INTO Rating (id, match_id, language_code, username, score, publication_date, opinion)
+SELECT id, match_id, language_code, username, score, publication_date, opinion
+FROM Rating_Duplicate;
+
+DROP TABLE IF EXISTS Rating_Duplicate;
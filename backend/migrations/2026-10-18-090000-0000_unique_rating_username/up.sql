@@ -0,0 +1,27 @@
+-- Ratings submitted before the constraint existed may contain duplicates. Only the first one
+-- published for a given username stays in Rating, the others are moved to Rating_Duplicate so
+-- that an operator can review them.
+CREATE TABLE Rating_Duplicate
+(
+  LIKE Rating,
+  archived_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  CONSTRAINT PK_RatingDuplicate
+    PRIMARY KEY (id)
+);
+
+WITH duplicate AS (
+  DELETE FROM Rating r
+  USING Rating older
+  WHERE r.match_id = older.match_id
+    AND r.username = older.username
+    AND (r.publication_date, r.id) > (older.publication_date, older.id)
+  RETURNING r.*
+)
+INSERT INTO Rating_Duplicate
+  (id, match_id, language_code, username, score, publication_date, opinion)
+SELECT id, match_id, language_code, username, score, publication_date, opinion
+FROM duplicate;
+
+ALTER TABLE Rating
+  ADD CONSTRAINT UQ_Rating_Match_Username
+    UNIQUE (match_id, username);
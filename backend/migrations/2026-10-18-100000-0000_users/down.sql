@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS Rating_Claim;
+ALTER TABLE Rating DROP COLUMN IF EXISTS user_id;
+DROP TABLE IF EXISTS Session CASCADE;
+DROP TABLE IF EXISTS Users CASCADE;
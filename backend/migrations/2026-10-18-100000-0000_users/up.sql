@@ -0,0 +1,62 @@
+CREATE TABLE Users
+(
+  id SERIAL,
+  username VARCHAR(32) NOT NULL,
+  password_hash TEXT NOT NULL,
+  creation_date TIMESTAMP NOT NULL,
+  CONSTRAINT PK_Users
+    PRIMARY KEY (id)
+);
+
+-- "Eva" and "eva" are the same person
+CREATE UNIQUE INDEX UQ_Users_Username ON Users (LOWER(username));
+
+CREATE TABLE Session
+(
+  id SERIAL,
+  user_id INTEGER NOT NULL,
+  -- SHA-256 of the token given to the client, the token itself is never stored
+  token_hash CHAR(64) NOT NULL,
+  creation_date TIMESTAMP NOT NULL,
+  expiration_date TIMESTAMP NOT NULL,
+  CONSTRAINT PK_Session
+    PRIMARY KEY (id),
+  CONSTRAINT UQ_Session_Token
+    UNIQUE (token_hash),
+  CONSTRAINT FK_Session_Users
+    FOREIGN KEY (user_id) REFERENCES Users(id)
+    ON DELETE CASCADE
+);
+
+-- ratings made before accounts existed keep a NULL author, until a claim is approved
+ALTER TABLE Rating
+  ADD COLUMN user_id INTEGER,
+  ADD CONSTRAINT FK_Rating_Users
+    FOREIGN KEY (user_id) REFERENCES Users(id)
+    ON DELETE SET NULL;
+
+-- An account asks for the anonymous ratings of a username, with anything proving it wrote them.
+-- Nothing changes until a moderator approves the claim.
+CREATE TABLE Rating_Claim
+(
+  id SERIAL,
+  user_id INTEGER NOT NULL,
+  username VARCHAR(32) NOT NULL,
+  proof TEXT NOT NULL,
+  creation_date TIMESTAMP NOT NULL,
+  -- NULL while the claim is pending
+  approved BOOLEAN,
+  moderator_id INTEGER,
+  resolution_date TIMESTAMP,
+  CONSTRAINT PK_RatingClaim
+    PRIMARY KEY (id),
+  CONSTRAINT FK_RatingClaim_Users
+    FOREIGN KEY (user_id) REFERENCES Users(id)
+    ON DELETE CASCADE,
+  CONSTRAINT FK_RatingClaim_Moderator
+    FOREIGN KEY (moderator_id) REFERENCES Users(id)
+    ON DELETE SET NULL
+);
+
+-- a username can only be claimed by one account at a time
+CREATE UNIQUE INDEX UQ_RatingClaim_Pending ON Rating_Claim (LOWER(username)) WHERE approved IS NULL;
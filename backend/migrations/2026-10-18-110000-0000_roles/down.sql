@@ -0,0 +1,2 @@
+ALTER TABLE Users DROP COLUMN IF EXISTS role_code;
+DROP TABLE IF EXISTS Role CASCADE;
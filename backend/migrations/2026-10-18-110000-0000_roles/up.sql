@@ -0,0 +1,13 @@
+-- from the least to the most privileged, each role can do everything the previous ones can
+CREATE TABLE Role (
+  code VARCHAR(16),
+  CONSTRAINT PK_Role
+    PRIMARY KEY (code)
+);
+
+INSERT INTO Role (code) VALUES ('reader'), ('rater'), ('editor'), ('moderator'), ('admin');
+
+ALTER TABLE Users
+  ADD COLUMN role_code VARCHAR(16) NOT NULL DEFAULT 'rater',
+  ADD CONSTRAINT FK_Users_Role
+    FOREIGN KEY (role_code) REFERENCES Role(code);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS Rating_Revision;
+ALTER TABLE Rating DROP COLUMN IF EXISTS edited_at;
@@ -0,0 +1,23 @@
+-- NULL until the rating is edited for the first time
+ALTER TABLE Rating ADD COLUMN edited_at TIMESTAMP;
+
+-- previous versions of the edited ratings, one row per edit
+CREATE TABLE Rating_Revision
+(
+  id SERIAL,
+  rating_id INTEGER NOT NULL,
+  score NUMERIC(3, 1) NOT NULL,
+  opinion TEXT,
+  -- when this version was replaced
+  revision_date TIMESTAMP NOT NULL,
+  -- the author or a moderator, NULL if the account was deleted since
+  editor_id INTEGER,
+  CONSTRAINT PK_RatingRevision
+    PRIMARY KEY (id),
+  CONSTRAINT FK_RatingRevision_Rating
+    FOREIGN KEY (rating_id) REFERENCES Rating(id)
+    ON DELETE CASCADE,
+  CONSTRAINT FK_RatingRevision_Users
+    FOREIGN KEY (editor_id) REFERENCES Users(id)
+    ON DELETE SET NULL
+);
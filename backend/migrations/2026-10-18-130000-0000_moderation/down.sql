@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS Moderation_Log;
+DROP TABLE IF EXISTS Rating_Flag;
+ALTER TABLE Rating DROP COLUMN IF EXISTS hidden;
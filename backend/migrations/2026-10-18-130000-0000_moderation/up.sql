@@ -0,0 +1,45 @@
+-- hidden ratings are kept but no longer published
+ALTER TABLE Rating ADD COLUMN hidden BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE Rating_Flag
+(
+  id SERIAL,
+  rating_id INTEGER NOT NULL,
+  user_id INTEGER NOT NULL,
+  reason TEXT NOT NULL,
+  creation_date TIMESTAMP NOT NULL,
+  -- set once a moderator hid or restored the rating
+  resolved BOOLEAN NOT NULL DEFAULT FALSE,
+  CONSTRAINT PK_RatingFlag
+    PRIMARY KEY (id),
+  -- a rating can only be flagged once by each account
+  CONSTRAINT UQ_RatingFlag_Rating_User
+    UNIQUE (rating_id, user_id),
+  CONSTRAINT FK_RatingFlag_Rating
+    FOREIGN KEY (rating_id) REFERENCES Rating(id)
+    ON DELETE CASCADE,
+  CONSTRAINT FK_RatingFlag_Users
+    FOREIGN KEY (user_id) REFERENCES Users(id)
+    ON DELETE CASCADE
+);
+
+CREATE INDEX IDX_RatingFlag_Unresolved ON Rating_Flag (rating_id) WHERE NOT resolved;
+
+-- audit trail of the moderators' actions. There is no foreign key on the rating, the trail
+-- must outlive the deleted ratings.
+CREATE TABLE Moderation_Log
+(
+  id SERIAL,
+  rating_id INTEGER NOT NULL,
+  moderator_id INTEGER,
+  action VARCHAR(16) NOT NULL,
+  reason TEXT,
+  action_date TIMESTAMP NOT NULL,
+  CONSTRAINT PK_ModerationLog
+    PRIMARY KEY (id),
+  CONSTRAINT FK_ModerationLog_Users
+    FOREIGN KEY (moderator_id) REFERENCES Users(id)
+    ON DELETE SET NULL,
+  CONSTRAINT CHK_ModerationLog_Action
+    CHECK (action IN ('hide', 'restore', 'delete'))
+);
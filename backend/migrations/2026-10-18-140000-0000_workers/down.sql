@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS Match_Participant;
+DROP TABLE IF EXISTS Worker_Alias;
+DROP TABLE IF EXISTS Worker;
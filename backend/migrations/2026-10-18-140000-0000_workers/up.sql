@@ -0,0 +1,82 @@
+CREATE TABLE Worker
+(
+  id SERIAL,
+  name VARCHAR(128) NOT NULL,
+  CONSTRAINT PK_Worker
+    PRIMARY KEY (id)
+);
+
+CREATE UNIQUE INDEX UQ_Worker_Name ON Worker (LOWER(name));
+
+-- other names of a worker. With dates, they make the history of its ring names.
+CREATE TABLE Worker_Alias
+(
+  id SERIAL,
+  worker_id INTEGER NOT NULL,
+  name VARCHAR(128) NOT NULL,
+  used_from DATE,
+  used_until DATE,
+  CONSTRAINT PK_WorkerAlias
+    PRIMARY KEY (id),
+  CONSTRAINT FK_WorkerAlias_Worker
+    FOREIGN KEY (worker_id) REFERENCES Worker(id)
+    ON DELETE CASCADE,
+  CONSTRAINT CHK_WorkerAlias_Dates
+    CHECK (used_from IS NULL OR used_until IS NULL OR used_from <= used_until)
+);
+
+CREATE UNIQUE INDEX UQ_WorkerAlias_Name ON Worker_Alias (LOWER(name));
+
+CREATE TABLE Match_Participant
+(
+  id SERIAL,
+  match_id INTEGER NOT NULL,
+  worker_id INTEGER NOT NULL,
+  -- sides are numbered from 1, in the order of Match.workers
+  side SMALLINT NOT NULL,
+  CONSTRAINT PK_MatchParticipant
+    PRIMARY KEY (id),
+  CONSTRAINT UQ_MatchParticipant_Match_Worker
+    UNIQUE (match_id, worker_id),
+  CONSTRAINT FK_MatchParticipant_Match
+    FOREIGN KEY (match_id) REFERENCES Match(id)
+    ON DELETE CASCADE,
+  CONSTRAINT FK_MatchParticipant_Worker
+    FOREIGN KEY (worker_id) REFERENCES Worker(id)
+    ON DELETE CASCADE,
+  CONSTRAINT CHK_MatchParticipant_Side
+    CHECK (side >= 1)
+);
+
+-- Match.workers is kept, the participants are parsed from it: sides are separated by "vs.",
+-- workers of a side by ",". Same rules as workers::parse_sides: "vs." only separates sides as a
+-- word of its own, and an empty side still counts, like the first one of "vs. The Young Bucks".
+-- The string is padded with spaces so that "vs." at the start or the end is a word too.
+CREATE TEMPORARY TABLE Parsed_Participant ON COMMIT DROP AS
+SELECT m.id AS match_id,
+  s.side,
+  n.position,
+  REGEXP_REPLACE(TRIM(n.name), '\s+', ' ', 'g') AS name
+FROM Match m
+  CROSS JOIN LATERAL REGEXP_SPLIT_TO_TABLE(' ' || m.workers || ' ', '\s+vs\.(?=\s)')
+    WITH ORDINALITY AS s(names, side)
+  CROSS JOIN LATERAL REGEXP_SPLIT_TO_TABLE(s.names, ',')
+    WITH ORDINALITY AS n(name, position)
+WHERE TRIM(n.name) <> '';
+
+-- the first spelling met is kept for the workers written with different cases
+INSERT INTO Worker (name)
+SELECT DISTINCT ON (LOWER(name)) name
+FROM Parsed_Participant
+ORDER BY LOWER(name), match_id, side, position;
+
+-- inserted in the order of the string, the participants are listed by side then id
+INSERT INTO Match_Participant (match_id, worker_id, side)
+SELECT match_id, worker_id, side
+FROM (
+  SELECT DISTINCT ON (p.match_id, w.id) p.match_id, w.id AS worker_id, p.side, p.position
+  FROM Parsed_Participant p
+    JOIN Worker w ON LOWER(w.name) = LOWER(p.name)
+  ORDER BY p.match_id, w.id, p.side, p.position
+) participants
+ORDER BY match_id, side, position;
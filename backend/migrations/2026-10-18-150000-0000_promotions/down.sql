@@ -0,0 +1,14 @@
+ALTER TABLE Event ADD COLUMN IF NOT EXISTS promotion VARCHAR(64);
+
+UPDATE Event e
+SET promotion = p.name
+FROM Promotion p
+WHERE p.id = e.promotion_id;
+
+ALTER TABLE Event
+  DROP CONSTRAINT IF EXISTS UQ_Name_Promotion,
+  DROP COLUMN IF EXISTS promotion_id,
+  ALTER COLUMN promotion SET NOT NULL,
+  ADD CONSTRAINT UQ_Name_Promotion
+    UNIQUE (name, promotion);
+DROP TABLE IF EXISTS Promotion;
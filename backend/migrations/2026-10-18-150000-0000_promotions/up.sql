@@ -0,0 +1,49 @@
+CREATE TABLE Promotion
+(
+  id SERIAL,
+  name VARCHAR(64) NOT NULL,
+  abbreviation VARCHAR(16),
+  country VARCHAR(64),
+  -- years of activity, the last one stays NULL while the promotion is active
+  active_from SMALLINT,
+  active_until SMALLINT,
+  CONSTRAINT PK_Promotion
+    PRIMARY KEY (id),
+  CONSTRAINT CHK_Promotion_Years
+    CHECK (active_from IS NULL OR active_until IS NULL OR active_from <= active_until)
+);
+
+-- a promotion is found by its name or its abbreviation, so the server also refuses an
+-- abbreviation equal to the name of another promotion, and the other way around
+CREATE UNIQUE INDEX UQ_Promotion_Name ON Promotion (LOWER(name));
+CREATE UNIQUE INDEX UQ_Promotion_Abbreviation ON Promotion (LOWER(abbreviation));
+
+-- one promotion for each way of writing it, unless it is already the name or the abbreviation
+-- of a promotion. The names and abbreviations that mean the same promotion, like "AEW" and
+-- "All Elite Wrestling", are then merged by the editors.
+INSERT INTO Promotion (name)
+SELECT DISTINCT ON (LOWER(e.promotion)) e.promotion
+FROM Event e
+WHERE NOT EXISTS (
+  SELECT 1
+  FROM Promotion p
+  WHERE LOWER(p.name) = LOWER(e.promotion) OR LOWER(p.abbreviation) = LOWER(e.promotion)
+)
+ORDER BY LOWER(e.promotion), e.promotion;
+
+ALTER TABLE Event ADD COLUMN promotion_id INTEGER;
+
+UPDATE Event e
+SET promotion_id = p.id
+FROM Promotion p
+WHERE LOWER(p.name) = LOWER(e.promotion) OR LOWER(p.abbreviation) = LOWER(e.promotion);
+
+-- the name of the promotion is only kept in Promotion
+ALTER TABLE Event
+  DROP CONSTRAINT UQ_Name_Promotion,
+  DROP COLUMN promotion,
+  ALTER COLUMN promotion_id SET NOT NULL,
+  ADD CONSTRAINT FK_Event_Promotion
+    FOREIGN KEY (promotion_id) REFERENCES Promotion(id),
+  ADD CONSTRAINT UQ_Name_Promotion
+    UNIQUE (name, promotion_id);
@@ -0,0 +1,3 @@
+ALTER TABLE Match DROP COLUMN IF EXISTS duration;
+ALTER TABLE Match DROP COLUMN IF EXISTS finish;
+ALTER TABLE Match DROP COLUMN IF EXISTS winning_side;
@@ -0,0 +1,15 @@
+-- optional result of a match, hidden from the clients that don't want spoilers
+ALTER TABLE Match ADD COLUMN winning_side SMALLINT;
+ALTER TABLE Match ADD COLUMN finish VARCHAR(16);
+-- in seconds
+ALTER TABLE Match ADD COLUMN duration INTEGER;
+
+ALTER TABLE Match ADD CONSTRAINT CHK_Match_WinningSide
+  CHECK (winning_side >= 1);
+ALTER TABLE Match ADD CONSTRAINT CHK_Match_Finish
+  CHECK (finish IN ('pinfall', 'submission', 'disqualification', 'count_out', 'no_contest', 'draw'));
+-- nobody wins a match ending without a decision
+ALTER TABLE Match ADD CONSTRAINT CHK_Match_Decision
+  CHECK (winning_side IS NULL OR finish IS NULL OR finish NOT IN ('no_contest', 'draw'));
+ALTER TABLE Match ADD CONSTRAINT CHK_Match_Duration
+  CHECK (duration >= 0);
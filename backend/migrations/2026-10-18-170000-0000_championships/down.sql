@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS Title_Match;
+DROP TABLE IF EXISTS Title_Reign;
+DROP TABLE IF EXISTS Championship;
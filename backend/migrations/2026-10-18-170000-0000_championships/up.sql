@@ -0,0 +1,62 @@
+CREATE TABLE Championship
+(
+  id SERIAL,
+  name VARCHAR(128) NOT NULL,
+  -- titles like the IWGP ones are defended on other promotions' shows, this is their owner
+  promotion_id INTEGER,
+  CONSTRAINT PK_Championship
+    PRIMARY KEY (id),
+  CONSTRAINT FK_Championship_Promotion
+    FOREIGN KEY (promotion_id) REFERENCES Promotion(id)
+    ON DELETE SET NULL
+);
+
+CREATE UNIQUE INDEX UQ_Championship_Name ON Championship (LOWER(name));
+
+-- a team holding a tag title is a worker of its own, like "The Young Bucks"
+CREATE TABLE Title_Reign
+(
+  id SERIAL,
+  championship_id INTEGER NOT NULL,
+  worker_id INTEGER NOT NULL,
+  start_date DATE NOT NULL,
+  -- unknown for the current champion
+  end_date DATE,
+  -- the match where the title was won, when it's on a card
+  won_match_id INTEGER,
+  CONSTRAINT PK_TitleReign
+    PRIMARY KEY (id),
+  CONSTRAINT UQ_TitleReign_Championship_Start
+    UNIQUE (championship_id, start_date),
+  CONSTRAINT FK_TitleReign_Championship
+    FOREIGN KEY (championship_id) REFERENCES Championship(id)
+    ON DELETE CASCADE,
+  CONSTRAINT FK_TitleReign_Worker
+    FOREIGN KEY (worker_id) REFERENCES Worker(id)
+    ON DELETE CASCADE,
+  CONSTRAINT FK_TitleReign_Match
+    FOREIGN KEY (won_match_id) REFERENCES Match(id)
+    ON DELETE SET NULL,
+  CONSTRAINT CHK_TitleReign_Dates
+    CHECK (end_date IS NULL OR start_date <= end_date)
+);
+
+-- a match can be contested for several titles at once, like "Winner Takes All" matches
+CREATE TABLE Title_Match
+(
+  id SERIAL,
+  match_id INTEGER NOT NULL,
+  championship_id INTEGER NOT NULL,
+  -- a match where the title didn't change hands is a defense
+  title_changed BOOLEAN NOT NULL DEFAULT FALSE,
+  CONSTRAINT PK_TitleMatch
+    PRIMARY KEY (id),
+  CONSTRAINT UQ_TitleMatch_Match_Championship
+    UNIQUE (match_id, championship_id),
+  CONSTRAINT FK_TitleMatch_Match
+    FOREIGN KEY (match_id) REFERENCES Match(id)
+    ON DELETE CASCADE,
+  CONSTRAINT FK_TitleMatch_Championship
+    FOREIGN KEY (championship_id) REFERENCES Championship(id)
+    ON DELETE CASCADE
+);
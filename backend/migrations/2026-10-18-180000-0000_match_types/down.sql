@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS Match_Classification;
+DROP TABLE IF EXISTS Match_Type_Label;
+DROP TABLE IF EXISTS Match_Type;
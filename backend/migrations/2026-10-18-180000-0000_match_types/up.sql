@@ -0,0 +1,80 @@
+-- stipulations, like "ladder" or "tag_team", named in each language by their labels
+CREATE TABLE Match_Type
+(
+  id SERIAL,
+  code VARCHAR(32) NOT NULL,
+  CONSTRAINT PK_MatchType
+    PRIMARY KEY (id),
+  CONSTRAINT UQ_MatchType_Code
+    UNIQUE (code)
+);
+
+CREATE TABLE Match_Type_Label
+(
+  match_type_id INTEGER NOT NULL,
+  language_code CHAR(3) NOT NULL,
+  label VARCHAR(64) NOT NULL,
+  CONSTRAINT PK_MatchTypeLabel
+    PRIMARY KEY (match_type_id, language_code),
+  CONSTRAINT FK_MatchTypeLabel_MatchType
+    FOREIGN KEY (match_type_id) REFERENCES Match_Type(id)
+    ON DELETE CASCADE,
+  CONSTRAINT FK_MatchTypeLabel_Language
+    FOREIGN KEY (language_code) REFERENCES Language(code)
+    ON DELETE CASCADE
+);
+
+-- a match can have several types, like a "Tag Team Ladder Match"
+CREATE TABLE Match_Classification
+(
+  match_id INTEGER NOT NULL,
+  match_type_id INTEGER NOT NULL,
+  CONSTRAINT PK_MatchClassification
+    PRIMARY KEY (match_id, match_type_id),
+  CONSTRAINT FK_MatchClassification_Match
+    FOREIGN KEY (match_id) REFERENCES Match(id)
+    ON DELETE CASCADE,
+  CONSTRAINT FK_MatchClassification_MatchType
+    FOREIGN KEY (match_type_id) REFERENCES Match_Type(id)
+    ON DELETE CASCADE
+);
+
+CREATE INDEX IDX_MatchClassification_MatchType ON Match_Classification (match_type_id);
+
+-- the types known on creation, with their labels. The existing matches are classified
+-- afterwards by `ratematch-srv classify-matches`, whose guesses the editors can review.
+CREATE TEMPORARY TABLE Known_Type
+(
+  code VARCHAR(32),
+  english VARCHAR(64),
+  french VARCHAR(64)
+);
+
+INSERT INTO Known_Type (code, english, french) VALUES
+  ('singles', 'Singles Match', 'Match simple'),
+  ('tag_team', 'Tag Team Match', 'Match en équipe'),
+  ('trios', 'Trios Match', 'Match en trio'),
+  ('triple_threat', 'Triple Threat Match', 'Match à trois'),
+  ('four_way', 'Four Way Match', 'Match à quatre'),
+  ('ladder', 'Ladder Match', 'Match à échelles'),
+  ('stretcher', 'Stretcher Match', 'Match du brancard'),
+  ('street_fight', 'Street Fight', 'Bagarre de rue'),
+  ('steel_cage', 'Steel Cage Match', 'Match en cage'),
+  ('texas_death', 'Texas Death Match', 'Match à mort du Texas'),
+  ('no_disqualification', 'No Disqualification Match', 'Match sans disqualification'),
+  ('lumberjack', 'Lumberjack Match', 'Match de bûcheron'),
+  ('battle_royal', 'Battle Royal', 'Bataille royale'),
+  ('gauntlet', 'Gauntlet Match', 'Match épreuve');
+
+INSERT INTO Match_Type (code)
+SELECT code FROM Known_Type;
+
+INSERT INTO Match_Type_Label (match_type_id, language_code, label)
+SELECT Match_Type.id, 'ENG', Known_Type.english
+FROM Match_Type JOIN Known_Type ON Known_Type.code = Match_Type.code;
+
+INSERT INTO Match_Type_Label (match_type_id, language_code, label)
+SELECT Match_Type.id, 'FRE', Known_Type.french
+FROM Match_Type JOIN Known_Type ON Known_Type.code = Match_Type.code;
+
+DROP TABLE Known_Type;
@@ -0,0 +1,2 @@
+ALTER TABLE Match DROP COLUMN IF EXISTS segment;
+ALTER TABLE Match DROP COLUMN IF EXISTS position;
@@ -0,0 +1,28 @@
+-- place of the match on the card, the first match first
+ALTER TABLE Match ADD COLUMN position SMALLINT;
+ALTER TABLE Match ADD COLUMN segment VARCHAR(16) NOT NULL DEFAULT 'main_card';
+
+-- the matches were entered in card order
+UPDATE Match SET position = Ordered.position
+FROM (
+  SELECT id, ROW_NUMBER() OVER (PARTITION BY event_id ORDER BY id) AS position
+  FROM Match
+) AS Ordered
+WHERE Match.id = Ordered.id;
+
+UPDATE Match SET segment = 'pre_show'
+WHERE id IN (
+  SELECT match_id FROM Match_Desc
+  WHERE language_code = 'ENG' AND description LIKE 'Pre-Show%'
+);
+
+-- the last match of a card may be a dark match, so the main events are left to the editors
+
+ALTER TABLE Match ALTER COLUMN position SET NOT NULL;
+ALTER TABLE Match ADD CONSTRAINT CHK_Match_Position
+  CHECK (position >= 1);
+-- deferred while a card is reordered, the positions are swapped in place
+ALTER TABLE Match ADD CONSTRAINT UQ_Match_Event_Position
+  UNIQUE (event_id, position) DEFERRABLE INITIALLY IMMEDIATE;
+ALTER TABLE Match ADD CONSTRAINT CHK_Match_Segment
+  CHECK (segment IN ('pre_show', 'main_card', 'main_event', 'dark_match'));
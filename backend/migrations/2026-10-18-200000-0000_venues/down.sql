@@ -0,0 +1,3 @@
+ALTER TABLE Event DROP COLUMN IF EXISTS attendance;
+ALTER TABLE Event DROP COLUMN IF EXISTS venue_id;
+DROP TABLE IF EXISTS Venue;
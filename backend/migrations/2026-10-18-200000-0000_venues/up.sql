@@ -0,0 +1,26 @@
+CREATE TABLE Venue
+(
+  id SERIAL,
+  name VARCHAR(128) NOT NULL,
+  city VARCHAR(64) NOT NULL,
+  country VARCHAR(64) NOT NULL,
+  capacity INTEGER,
+  CONSTRAINT PK_Venue
+    PRIMARY KEY (id),
+  -- arenas are often named after sponsors, the same name can be found in two cities
+  CONSTRAINT UQ_Venue_Name_City
+    UNIQUE (name, city),
+  CONSTRAINT CHK_Venue_Capacity
+    CHECK (capacity > 0)
+);
+
+ALTER TABLE Event ADD COLUMN venue_id INTEGER;
+ALTER TABLE Event ADD COLUMN attendance INTEGER;
+
+ALTER TABLE Event ADD CONSTRAINT FK_Event_Venue
+  FOREIGN KEY (venue_id) REFERENCES Venue(id)
+  ON DELETE SET NULL;
+ALTER TABLE Event ADD CONSTRAINT CHK_Event_Attendance
+  CHECK (attendance >= 0);
+
+CREATE INDEX IDX_Event_Venue ON Event (venue_id);
@@ -0,0 +1,2 @@
+ALTER TABLE Event DROP COLUMN IF EXISTS series_id;
+DROP TABLE IF EXISTS Event_Series;
@@ -0,0 +1,47 @@
+-- recurring shows, like every Double or Nothing or every WrestleMania
+CREATE TABLE Event_Series
+(
+  id SERIAL,
+  name VARCHAR(128) NOT NULL,
+  promotion_id INTEGER,
+  CONSTRAINT PK_EventSeries
+    PRIMARY KEY (id),
+  CONSTRAINT FK_EventSeries_Promotion
+    FOREIGN KEY (promotion_id) REFERENCES Promotion(id)
+    ON DELETE SET NULL
+);
+
+-- two promotions can run shows with the same name
+CREATE UNIQUE INDEX UQ_EventSeries_Name ON Event_Series (LOWER(name), promotion_id);
+
+ALTER TABLE Event ADD COLUMN series_id INTEGER;
+ALTER TABLE Event ADD CONSTRAINT FK_Event_EventSeries
+  FOREIGN KEY (series_id) REFERENCES Event_Series(id)
+  ON DELETE SET NULL;
+
+CREATE INDEX IDX_Event_EventSeries ON Event (series_id);
+
+-- the yearly shows are named after their series, followed by the year. A name is only taken
+-- for a series once it came back at least once in the same promotion, the other series are
+-- created by the editors.
+CREATE TEMPORARY TABLE Yearly_Event ON COMMIT DROP AS
+SELECT id, promotion_id, date, SUBSTRING(name FROM '^(.*\S)\s+\d{4}$') AS series_name
+FROM Event
+WHERE name ~ '^.*\S\s+\d{4}$';
+
+INSERT INTO Event_Series (name, promotion_id)
+SELECT DISTINCT ON (LOWER(series_name), promotion_id) series_name, promotion_id
+FROM Yearly_Event
+WHERE (LOWER(series_name), promotion_id) IN (
+  SELECT LOWER(series_name), promotion_id
+  FROM Yearly_Event
+  GROUP BY LOWER(series_name), promotion_id
+  HAVING COUNT(*) >= 2
+)
+ORDER BY LOWER(series_name), promotion_id, date;
+
+UPDATE Event SET series_id = Event_Series.id
+FROM Yearly_Event, Event_Series
+WHERE Yearly_Event.id = Event.id
+  AND LOWER(Yearly_Event.series_name) = LOWER(Event_Series.name)
+  AND Yearly_Event.promotion_id = Event_Series.promotion_id;
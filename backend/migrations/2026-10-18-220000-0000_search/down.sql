@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS IDX_Rating_Search;
+DROP INDEX IF EXISTS IDX_MatchDesc_Search;
+DROP INDEX IF EXISTS IDX_Match_Search;
+DROP FUNCTION IF EXISTS Search_Config;
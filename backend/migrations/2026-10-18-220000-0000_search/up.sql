@@ -0,0 +1,21 @@
+-- text search configuration of the texts written in each language, the other texts (names of
+-- the events, promotions and workers) are indexed as they are
+CREATE FUNCTION Search_Config(language_code TEXT) RETURNS REGCONFIG
+  LANGUAGE SQL IMMUTABLE PARALLEL SAFE
+  RETURN CASE language_code
+    WHEN 'FRE' THEN 'french'::REGCONFIG
+    WHEN 'ENG' THEN 'english'::REGCONFIG
+    ELSE 'simple'::REGCONFIG
+  END;
+
+-- the searches must use the same expressions to use these indexes. The events are searched
+-- with the name of their promotion, from another table, so they are read without index.
+
+CREATE INDEX IDX_Match_Search ON Match
+  USING GIN (TO_TSVECTOR('simple', workers));
+
+CREATE INDEX IDX_MatchDesc_Search ON Match_Desc
+  USING GIN (TO_TSVECTOR(Search_Config(language_code), description));
+
+CREATE INDEX IDX_Rating_Search ON Rating
+  USING GIN (TO_TSVECTOR(Search_Config(language_code), opinion));
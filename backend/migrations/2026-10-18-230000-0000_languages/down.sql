@@ -0,0 +1,3 @@
+ALTER TABLE Language DROP CONSTRAINT IF EXISTS CHK_Language_Code;
+ALTER TABLE Language DROP COLUMN IF EXISTS native_name;
+ALTER TABLE Language DROP COLUMN IF EXISTS name;
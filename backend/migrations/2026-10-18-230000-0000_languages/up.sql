@@ -0,0 +1,11 @@
+-- the languages are managed by the administrators instead of being fixed in the code
+ALTER TABLE Language ADD COLUMN name VARCHAR(64);
+ALTER TABLE Language ADD COLUMN native_name VARCHAR(64);
+
+UPDATE Language SET name = 'French', native_name = 'Français' WHERE code = 'FRE';
+UPDATE Language SET name = 'English', native_name = 'English' WHERE code = 'ENG';
+UPDATE Language SET name = code, native_name = code WHERE name IS NULL;
+
+ALTER TABLE Language ALTER COLUMN name SET NOT NULL;
+ALTER TABLE Language ALTER COLUMN native_name SET NOT NULL;
+ALTER TABLE Language ADD CONSTRAINT CHK_Language_Code CHECK (code ~ '^[A-Z]{3}$');
@@ -0,0 +1,3 @@
+ALTER TABLE Language DROP CONSTRAINT IF EXISTS CHK_Language_ShortCode;
+ALTER TABLE Language DROP CONSTRAINT IF EXISTS UQ_Language_ShortCode;
+ALTER TABLE Language DROP COLUMN IF EXISTS short_code;
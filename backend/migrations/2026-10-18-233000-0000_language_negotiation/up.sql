@@ -0,0 +1,8 @@
+-- two-letter code of the language, used to match the tags of the Accept-Language header
+ALTER TABLE Language ADD COLUMN short_code CHAR(2);
+
+UPDATE Language SET short_code = 'fr' WHERE code = 'FRE';
+UPDATE Language SET short_code = 'en' WHERE code = 'ENG';
+
+ALTER TABLE Language ADD CONSTRAINT UQ_Language_ShortCode UNIQUE (short_code);
+ALTER TABLE Language ADD CONSTRAINT CHK_Language_ShortCode CHECK (short_code ~ '^[a-z]{2}$');
@@ -0,0 +1,20 @@
+DROP INDEX IF EXISTS UQ_Rating_Match_Anonymous;
+ALTER TABLE Rating DROP CONSTRAINT IF EXISTS UQ_Rating_Match_User;
+
+WITH duplicate AS (
+  DELETE FROM Rating r
+  USING Rating older
+  WHERE r.match_id = older.match_id
+    AND r.username = older.username
+    AND (r.publication_date, r.id) > (older.publication_date, older.id)
+  RETURNING r.*
+)
+INSERT INTO Rating_Duplicate
+  (id, match_id, language_code, username, score, publication_date, opinion, user_id)
+SELECT id, match_id, language_code, username, score, publication_date, opinion, user_id
+FROM duplicate;
+
+ALTER TABLE Rating
+  ADD CONSTRAINT UQ_Rating_Match_Username
+    UNIQUE (match_id, username);
+ALTER TABLE Rating_Duplicate DROP COLUMN IF EXISTS user_id;
@@ -0,0 +1,27 @@
+-- A rating is unique per account, and per username whatever its case among the anonymous ones
+-- only: an account named like the author of old anonymous ratings can still rate these matches.
+-- The ratings breaking these rules are moved to Rating_Duplicate, the first one published stays.
+ALTER TABLE Rating_Duplicate
+  ADD COLUMN user_id INTEGER;
+
+WITH duplicate AS (
+  DELETE FROM Rating r
+  USING Rating older
+  WHERE r.match_id = older.match_id
+    AND (r.user_id = older.user_id
+      OR (r.user_id IS NULL AND older.user_id IS NULL
+        AND LOWER(r.username) = LOWER(older.username)))
+    AND (r.publication_date, r.id) > (older.publication_date, older.id)
+  RETURNING r.*
+)
+INSERT INTO Rating_Duplicate
+  (id, match_id, language_code, username, score, publication_date, opinion, user_id)
+SELECT id, match_id, language_code, username, score, publication_date, opinion, user_id
+FROM duplicate;
+
+ALTER TABLE Rating
+  DROP CONSTRAINT UQ_Rating_Match_Username,
+  ADD CONSTRAINT UQ_Rating_Match_User
+    UNIQUE (match_id, user_id);
+
+CREATE UNIQUE INDEX UQ_Rating_Match_Anonymous ON Rating (match_id, LOWER(username)) WHERE user_id IS NULL;
@@ -0,0 +1,202 @@
+use argon2::{
+    Argon2,
+    password_hash::{
+        PasswordHash, PasswordHasher, PasswordVerifier, SaltString,
+        rand_core::{OsRng, RngCore},
+    },
+};
+use chrono::{Duration, NaiveDateTime, Utc};
+use rocket::{
+    Request,
+    http::Status,
+    request::{FromRequest, Outcome},
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::marker::PhantomData;
+
+use crate::{
+    AppState,
+    database::DatabaseOperations,
+    entities::{NewSession, Rating, Role, User},
+    errors::Error,
+};
+
+/// How long a session stays valid after login
+const SESSION_DURATION_DAYS: i64 = 30;
+/// Random bytes in a session token
+const TOKEN_BYTES: usize = 32;
+
+pub fn hash_password(password: &str) -> Result<String, Error> {
+    let salt = SaltString::generate(&mut OsRng);
+
+    match Argon2::default().hash_password(password.as_bytes(), &salt) {
+        Ok(hash) => Ok(hash.to_string()),
+        Err(err) => Err(Error::INEEDTOLOG { e: err.to_string() }),
+    }
+}
+
+pub fn verify_password(password: &str, password_hash: &str) -> Result<bool, Error> {
+    let password_hash = match PasswordHash::new(password_hash) {
+        Ok(password_hash) => password_hash,
+        Err(err) => return Err(Error::INEEDTOLOG { e: err.to_string() }),
+    };
+
+    Ok(Argon2::default()
+        .verify_password(password.as_bytes(), &password_hash)
+        .is_ok())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Only the hash of the tokens is stored, so a leaked database doesn't leak sessions
+pub fn hash_token(token: &str) -> String {
+    to_hex(&Sha256::digest(token.as_bytes()))
+}
+
+/// Token sent back to a client on login, to put in the `Authorization: Bearer` header
+#[derive(Serialize)]
+pub struct SessionToken {
+    token: String,
+    expiration_date: NaiveDateTime,
+    user: User,
+}
+
+/// Opens a new session for the user
+pub fn new_session(database: &impl DatabaseOperations, user: User) -> Result<SessionToken, Error> {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    let token = to_hex(&bytes);
+
+    let now = Utc::now().naive_utc();
+    let expiration_date = now + Duration::days(SESSION_DURATION_DAYS);
+    database.new_session(NewSession {
+        user_id: user.id(),
+        token_hash: hash_token(&token),
+        creation_date: now,
+        expiration_date,
+    })?;
+
+    Ok(SessionToken {
+        token,
+        expiration_date,
+        user,
+    })
+}
+
+fn app_state<'r>(request: &'r Request<'_>) -> Result<&'r AppState, (Status, Error)> {
+    match request.rocket().state::<AppState>() {
+        Some(state) => Ok(state),
+        None => Err((
+            Status::InternalServerError,
+            Error::INEEDTOLOG {
+                e: "AppState is not managed".to_string(),
+            },
+        )),
+    }
+}
+
+/// Token of an `Authorization: Bearer <token>` header
+pub fn bearer_token<'r>(request: &'r Request<'_>) -> Option<&'r str> {
+    request
+        .headers()
+        .get_one("Authorization")
+        .and_then(|header| header.strip_prefix("Bearer "))
+}
+
+/// Request guard for the endpoints needing an account, expecting an
+/// `Authorization: Bearer <token>` header with a session token
+pub struct AuthenticatedUser {
+    pub user: User,
+    pub token_hash: String,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AuthenticatedUser {
+    type Error = Error;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let state = match app_state(request) {
+            Ok(state) => state,
+            Err(err) => return Outcome::Error(err),
+        };
+
+        let token = match bearer_token(request) {
+            Some(token) => token,
+            None => return Outcome::Error((Status::Unauthorized, Error::Unauthorized)),
+        };
+
+        let token_hash = hash_token(token);
+        match state.database.get_user_by_session(&token_hash) {
+            Ok(user) => Outcome::Success(AuthenticatedUser { user, token_hash }),
+            // unknown or expired session
+            Err(Error::ResourceDoesNotExists) => {
+                Outcome::Error((Status::Unauthorized, Error::Unauthorized))
+            }
+            Err(err) => Outcome::Error((err.status(), err)),
+        }
+    }
+}
+
+/// Minimum role needed by an `Authorized` guard
+pub trait RequiredRole {
+    const ROLE: Role;
+}
+
+pub struct Rater;
+pub struct Editor;
+pub struct Moderator;
+pub struct Admin;
+
+impl RequiredRole for Rater {
+    const ROLE: Role = Role::Rater;
+}
+
+impl RequiredRole for Editor {
+    const ROLE: Role = Role::Editor;
+}
+
+impl RequiredRole for Moderator {
+    const ROLE: Role = Role::Moderator;
+}
+
+impl RequiredRole for Admin {
+    const ROLE: Role = Role::Admin;
+}
+
+/// Request guard for the endpoints needing at least a given role, like `Authorized<Editor>`.
+/// Unauthenticated requests get a 401, authenticated ones without the role a 403.
+pub struct Authorized<R: RequiredRole> {
+    pub user: User,
+    role: PhantomData<R>,
+}
+
+#[rocket::async_trait]
+impl<'r, R: RequiredRole> FromRequest<'r> for Authorized<R> {
+    type Error = Error;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match request.guard::<AuthenticatedUser>().await {
+            Outcome::Success(authenticated) if authenticated.user.role() >= R::ROLE => {
+                Outcome::Success(Authorized {
+                    user: authenticated.user,
+                    role: PhantomData,
+                })
+            }
+            Outcome::Success(_) => Outcome::Error((Status::Forbidden, Error::Forbidden)),
+            Outcome::Error(err) => Outcome::Error(err),
+            Outcome::Forward(status) => Outcome::Forward(status),
+        }
+    }
+}
+
+/// A rating can only be changed by its author or a moderator
+pub fn check_rating_author(user: &User, rating: &Rating) -> Result<(), Error> {
+    if rating.user_id() == Some(user.id()) || user.role() >= Role::Moderator {
+        Ok(())
+    } else {
+        Err(Error::Forbidden)
+    }
+}
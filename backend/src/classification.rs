@@ -0,0 +1,87 @@
+/// Phrases of the English descriptions each match type is guessed from. They are matched as
+/// whole words whatever their case, so "Cage" doesn't find "Cagematch" nor "Rumble" a "Rumble
+/// Roses" match.
+const PHRASES: &[(&str, &[&str])] = &[
+    ("singles", &["singles match"]),
+    ("tag_team", &["tag team"]),
+    ("trios", &["trios", "six man tag", "6 man tag"]),
+    ("triple_threat", &["triple threat", "three way", "3 way"]),
+    ("four_way", &["four way", "4 way"]),
+    ("ladder", &["ladder"]),
+    ("stretcher", &["stretcher"]),
+    ("street_fight", &["street fight"]),
+    ("steel_cage", &["steel cage", "cage match"]),
+    ("texas_death", &["texas death"]),
+    (
+        "no_disqualification",
+        &["no disqualification", "no dq", "no holds barred"],
+    ),
+    ("lumberjack", &["lumberjack"]),
+    (
+        "battle_royal",
+        &["battle royal", "battle royale", "royal rumble"],
+    ),
+    ("gauntlet", &["gauntlet"]),
+];
+
+/// Lowercases a text and keeps only its words, separated by single spaces and surrounded by
+/// spaces, so "Six-Man Tag Team" becomes " six man tag team "
+fn words(text: &str) -> String {
+    let words = text
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(" {} ", words)
+}
+
+/// Returns the codes of the match types an English description seems to describe. These are
+/// only guesses, to be reviewed by the editors before being saved.
+pub fn guess_types(description: &str) -> Vec<&'static str> {
+    let description = words(description);
+    let mut codes = PHRASES
+        .iter()
+        .filter(|(_, phrases)| {
+            phrases
+                .iter()
+                .any(|phrase| description.contains(&words(phrase)))
+        })
+        .map(|(code, _)| *code)
+        .collect::<Vec<_>>();
+
+    // a six man tag team match is only a trios match
+    if codes.contains(&"trios") {
+        codes.retain(|code| *code != "tag_team");
+    }
+    codes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::guess_types;
+
+    #[test]
+    fn ignores_the_case_and_the_punctuation() {
+        assert_eq!(
+            guess_types("tag team ladder match"),
+            vec!["tag_team", "ladder"]
+        );
+        assert_eq!(
+            guess_types("No-DQ Street Fight"),
+            vec!["street_fight", "no_disqualification"]
+        );
+    }
+
+    #[test]
+    fn only_matches_whole_words() {
+        assert!(guess_types("Cagematch Rumble Roses Rambo").is_empty());
+        assert_eq!(guess_types("Steel Cage Match"), vec!["steel_cage"]);
+    }
+
+    #[test]
+    fn six_man_tag_team_matches_are_only_trios() {
+        assert_eq!(guess_types("Six Man Tag Team Match"), vec!["trios"]);
+        assert_eq!(guess_types("Tag Team Match"), vec!["tag_team"]);
+    }
+}
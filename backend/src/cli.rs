@@ -0,0 +1,219 @@
+use chrono::Utc;
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::{self, BufRead, Write},
+    path::Path,
+};
+
+use crate::{
+    auth, classification,
+    database::{Database, DatabaseOperations, ManageDatabaseConnection},
+    endpoints::RequestAccount,
+    entities::{Language, NewUser, Role},
+    errors::Error,
+    export::{ExportFilter, ExportFormat, ExportWriter},
+    import::{self, ImportFormat},
+};
+
+const USAGE: &str = "Usage:
+  ratematch-srv                                 Starts the server
+  ratematch-srv import <file.json|file.jsonl|file.csv> [--dry-run]
+                                                Imports the cards described in the file
+  ratematch-srv export [--format jsonl|csv] [--promotion <name>] [--from <YYYY-MM-DD>] [--to <YYYY-MM-DD>]
+                                                Writes every card, with its ratings, on the
+                                                standard output
+  ratematch-srv classify-matches [--apply]      Guesses the types of the matches without any from
+                                                their English description, and lists them on the
+                                                standard output as CSV. The guesses are only saved
+                                                with --apply, the editors then correct them
+  ratematch-srv create-admin <username>         Creates the first administrator, or promotes an
+                                                existing account. The password of a new account
+                                                is read from RATEMATCH_ADMIN_PASSWORD, or else
+                                                from the standard input";
+
+/// Runs a command given to the binary instead of starting the server, and returns the exit code
+pub fn run(args: &[String]) -> i32 {
+    let result = match args.first().map(String::as_str) {
+        Some("import") => import(&args[1..]),
+        Some("export") => export(&args[1..]),
+        Some("classify-matches") => classify_matches(&args[1..]),
+        Some("create-admin") => create_admin(&args[1..]),
+        Some("help") | Some("--help") | Some("-h") => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => Err(USAGE.to_string()),
+    };
+
+    match result {
+        Ok(()) => 0,
+        Err(message) => {
+            eprintln!("{}", message);
+            1
+        }
+    }
+}
+
+/// Value following `--name` in the arguments
+fn option<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+fn import(args: &[String]) -> Result<(), String> {
+    let dry_run = args.iter().any(|arg| arg == "--dry-run");
+    let file = match args.iter().find(|arg| !arg.starts_with("--")) {
+        Some(file) => Path::new(file),
+        None => return Err(USAGE.to_string()),
+    };
+
+    let format = file
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .and_then(ImportFormat::from_extension)
+        .ok_or("The file must have a .json or .csv extension.")?;
+    let document = fs::read_to_string(file)
+        .map_err(|err| format!("Could not read {}: {}", file.display(), err))?;
+
+    let cards = import::parse(&document, format).map_err(|err| err.to_string())?;
+    let report = Database::new()
+        .import_cards(cards, dry_run)
+        .map_err(|err| err.to_string())?;
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&report).map_err(|err| err.to_string())?
+    );
+    Ok(())
+}
+
+fn export(args: &[String]) -> Result<(), String> {
+    let format = match option(args, "--format") {
+        Some(format) => ExportFormat::from_name(format).map_err(|err| err.to_string())?,
+        None => ExportFormat::JsonLines,
+    };
+    let filter = ExportFilter::new(
+        option(args, "--promotion").map(str::to_string),
+        option(args, "--from"),
+        option(args, "--to"),
+    )
+    .map_err(|err| err.to_string())?;
+
+    let mut writer = ExportWriter::new(format);
+    let mut stdout = io::stdout().lock();
+    Database::new()
+        .export_cards(&filter, &mut |card| {
+            stdout
+                .write_all(writer.write(card)?.as_bytes())
+                .map_err(|err| Error::INEEDTOLOG { e: err.to_string() })
+        })
+        .map_err(|err| err.to_string())
+}
+
+fn classify_matches(args: &[String]) -> Result<(), String> {
+    let apply = args.iter().any(|arg| arg == "--apply");
+
+    let database = Database::new();
+    let english = Language::ENGLISH;
+    // the types renamed or deleted by the editors are not guessed anymore
+    let match_types = database
+        .get_match_types(english)
+        .map_err(|err| err.to_string())?
+        .into_iter()
+        .map(|localized| {
+            let match_type = localized.match_type();
+            (match_type.code().to_string(), match_type.id())
+        })
+        .collect::<HashMap<_, _>>();
+    let descriptions = database
+        .get_unclassified_descriptions(english)
+        .map_err(|err| err.to_string())?;
+
+    let mut writer = csv::Writer::from_writer(io::stdout().lock());
+    writer
+        .write_record(["match_id", "types", "description"])
+        .map_err(|err| err.to_string())?;
+    for description in descriptions {
+        let codes = classification::guess_types(description.description())
+            .into_iter()
+            .filter(|code| match_types.contains_key(*code))
+            .collect::<Vec<_>>();
+        if codes.is_empty() {
+            continue;
+        }
+
+        writer
+            .write_record([
+                description.match_id().to_string().as_str(),
+                codes.join(" ").as_str(),
+                description.description(),
+            ])
+            .map_err(|err| err.to_string())?;
+        if apply {
+            database
+                .set_match_types(
+                    description.match_id(),
+                    codes.iter().map(|code| match_types[*code]).collect(),
+                )
+                .map_err(|err| err.to_string())?;
+        }
+    }
+
+    writer.flush().map_err(|err| err.to_string())
+}
+
+fn create_admin(args: &[String]) -> Result<(), String> {
+    let username = match args.first() {
+        Some(username) => username.trim(),
+        None => return Err(USAGE.to_string()),
+    };
+
+    let database = Database::new();
+    // the next administrators are named through the API by this one
+    if database.has_admin().map_err(|err| err.to_string())? {
+        return Err("There is already an administrator.".to_string());
+    }
+
+    let user = match database.get_user_by_username(username) {
+        Ok(user) => user,
+        Err(Error::ResourceDoesNotExists) => {
+            let password = match env::var("RATEMATCH_ADMIN_PASSWORD") {
+                Ok(password) => password,
+                Err(_) => {
+                    eprintln!("Password for {}:", username);
+                    let mut password = String::new();
+                    io::stdin()
+                        .lock()
+                        .read_line(&mut password)
+                        .map_err(|err| err.to_string())?;
+                    password.trim_end_matches(['\r', '\n']).to_string()
+                }
+            };
+
+            let account = RequestAccount {
+                username: username.to_string(),
+                password,
+            };
+            account.validate().map_err(|err| err.to_string())?;
+
+            database
+                .new_user(NewUser {
+                    username: account.username,
+                    password_hash: auth::hash_password(&account.password)
+                        .map_err(|err| err.to_string())?,
+                    creation_date: Utc::now().naive_utc(),
+                })
+                .map_err(|err| err.to_string())?
+        }
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let user = database
+        .set_user_role(user.id(), Role::Admin)
+        .map_err(|err| err.to_string())?;
+    println!("{} is now an administrator.", user.username());
+    Ok(())
+}
@@ -1,24 +1,90 @@
 use bigdecimal::BigDecimal;
+use chrono::Utc;
 use core::panic;
 use diesel::{
-    dsl::{self, avg},
+    connection::{AnsiTransactionManager, TransactionManager},
+    dsl,
+    expression::{SqlLiteral, functions::declare_sql_function},
+    pg::Pg,
     prelude::*,
+    query_dsl::methods::OrderDsl,
     r2d2::{ConnectionManager, Pool, PooledConnection},
+    result::{DatabaseErrorKind, Error as DieselError},
+    sql_types::{Nullable, SqlType, Text, VarChar},
 };
 use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
 use dotenvy::dotenv;
-use std::env;
+use std::{env, mem};
 
 use crate::{
-    entities::{Event, Language, Match, MatchDesc, NewRating, Rating},
+    entities::{
+        Championship, ContestedTitle, Event, EventChanges, EventHit, EventSeries, FlaggedRating,
+        Language, LanguageInfo, LocalizedDescription, LocalizedLabel, LocalizedMatchType, Match,
+        MatchChanges, MatchDesc, MatchDetails, MatchHit, MatchResult, MatchType, ModerationAction,
+        ModerationLog, NewChampionship, NewEvent, NewEventSeries, NewMatch, NewMatchClassification,
+        NewMatchDesc, NewMatchParticipant, NewMatchType, NewMatchTypeLabel, NewModerationLog,
+        NewPromotion, NewRating, NewRatingClaim, NewRatingFlag, NewRatingRevision, NewSession,
+        NewTitleMatch, NewTitleReign, NewUser, NewVenue, NewWorker, NewWorkerAlias, Participant,
+        Promotion, RatedEvent, RatedMatch, RatedVenue, Rating, RatingChanges, RatingClaim,
+        RatingFlag, RatingHit, RatingRevision, ReignDetails, Role, SearchResults, Segment,
+        TitleHistory, TitleReign, User, Venue, Worker, WorkerAlias,
+    },
     errors::Error,
-    pagination::{Page, Paginate},
-    schema::{event, match_, match_desc, rating},
+    export::ExportFilter,
+    import::{CardEvent, ImportCard, ImportMatch, ImportRating, ImportReport, ImportedEvent},
+    pagination::{
+        Cursor, CursorPage, DEFAULT_PER_PAGE, EventSort, Order, Page, Paginate, RatingSort,
+    },
+    schema::{
+        championship, event, event_series, language, match_, match_classification, match_desc,
+        match_participant, match_type, match_type_label, moderation_log, promotion, rating,
+        rating_claim, rating_flag, rating_revision, session, title_match, title_reign, users,
+        venue, worker, worker_alias,
+    },
+    validation::FieldError,
+    workers,
 };
 
+#[declare_sql_function]
+extern "SQL" {
+    fn lower(x: VarChar) -> VarChar;
+    #[sql_name = "lower"]
+    fn lower_nullable(x: Nullable<VarChar>) -> Nullable<VarChar>;
+    /// Text search configuration of a language, defined by the search migration
+    fn search_config(language_code: Text) -> RegConfig;
+    fn to_tsvector(config: RegConfig, document: Text) -> TsVector;
+    fn websearch_to_tsquery(config: RegConfig, query: Text) -> TsQuery;
+    fn ts_rank(document: TsVector, query: TsQuery) -> Float;
+    fn ts_headline(config: RegConfig, document: Text, query: TsQuery, options: Text) -> Text;
+}
+
+/// Postgres full-text search types, they are never loaded so they only exist in the queries
+#[derive(SqlType, Clone, Copy)]
+#[diesel(postgres_type(name = "regconfig"))]
+pub struct RegConfig;
+
+#[derive(SqlType, Clone, Copy)]
+#[diesel(postgres_type(name = "tsvector"))]
+pub struct TsVector;
+
+#[derive(SqlType, Clone, Copy)]
+#[diesel(postgres_type(name = "tsquery"))]
+pub struct TsQuery;
+
+diesel::infix_operator!(TsMatches, " @@ ", backend: Pg);
+
+/// Configuration used for the names, which must not be stemmed like the texts of a language
+fn simple_config() -> SqlLiteral<RegConfig> {
+    dsl::sql("'simple'")
+}
+
+/// Highlights at most two extracts of the searched text
+const SNIPPET_OPTIONS: &str = "StartSel=<mark>, StopSel=</mark>, MaxFragments=2, MaxWords=20";
+
 // to automatically make the tables
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
 
+#[derive(Clone)]
 pub struct Database {
     pool: Pool<ConnectionManager<PgConnection>>,
 }
@@ -64,52 +130,1009 @@ impl ManageDatabaseConnection for Database {
 
 const ITEMS_PER_PAGE: i64 = 4;
 
+/// Sorts the ratings of any list, the ratings published at the same time or with the same score
+/// keep the same order from one page to the next
+fn sort_ratings<Q>(ratings: Q, sort: RatingSort, order: Order) -> Q
+where
+    Q: QueryDsl
+        + OrderDsl<(dsl::Asc<rating::publication_date>, dsl::Asc<rating::id>), Output = Q>
+        + OrderDsl<(dsl::Desc<rating::publication_date>, dsl::Desc<rating::id>), Output = Q>
+        + OrderDsl<(dsl::Asc<rating::score>, dsl::Asc<rating::id>), Output = Q>
+        + OrderDsl<(dsl::Desc<rating::score>, dsl::Desc<rating::id>), Output = Q>,
+{
+    match (sort, order) {
+        (RatingSort::Date, Order::Asc) => {
+            ratings.order_by((rating::publication_date.asc(), rating::id.asc()))
+        }
+        (RatingSort::Date, Order::Desc) => {
+            ratings.order_by((rating::publication_date.desc(), rating::id.desc()))
+        }
+        (RatingSort::Score, Order::Asc) => {
+            ratings.order_by((rating::score.asc(), rating::id.asc()))
+        }
+        (RatingSort::Score, Order::Desc) => {
+            ratings.order_by((rating::score.desc(), rating::id.desc()))
+        }
+    }
+}
+
 // Trait used for specifying the database operations.
 pub trait DatabaseOperations {
     /// Returns a list of events, page by page
-    fn get_events(&self, page: i64) -> Result<Page<Event>, Error>;
+    fn get_events(
+        &self,
+        page: i64,
+        per_page: Option<i64>,
+        sort: EventSort,
+        order: Order,
+    ) -> Result<Page<Event>, Error>;
     /// Returns informations about an event
     fn get_event_by(&self, event_id: i32) -> Result<Event, Error>;
     /// Returns information about a match
     fn get_match_by(&self, match_id: i32) -> Result<Match, Error>;
-    /// Returns localized description about a match
-    fn get_match_description(&self, match_id: i32, language: Language) -> Result<MatchDesc, Error>;
-    /// Returns the list of matches associated with an event
-    fn get_card(&self, event_id: i32) -> Result<Vec<Match>, Error>;
-    /// Returns the global ratings (in a specific language), page by page
-    fn get_ratings(&self, page: i64, language: Language) -> Result<Page<Rating>, Error>;
+    /// Returns every localized description of a match, ordered by language
+    fn get_match_descriptions(&self, match_id: i32) -> Result<Vec<MatchDesc>, Error>;
+    /// Returns information about a match, with its participants
+    fn get_match_details(&self, match_id: i32) -> Result<MatchDetails, Error>;
+    /// Returns the list of matches associated with an event, with their participants, optionally
+    /// only the ones of a type
+    fn get_card(&self, event_id: i32, match_type: Option<&str>)
+    -> Result<Vec<MatchDetails>, Error>;
+    /// Returns the global ratings (in a specific language), page by page, optionally only the
+    /// ones of matches of a type
+    fn get_ratings(
+        &self,
+        page: i64,
+        per_page: Option<i64>,
+        language: Language,
+        match_type: Option<&str>,
+        sort: RatingSort,
+        order: Order,
+    ) -> Result<Page<Rating>, Error>;
+    /// Returns the global ratings (in a specific language) from the most recent, a page after or
+    /// before a cursor. They are only counted if asked.
+    fn get_ratings_feed(
+        &self,
+        cursor: Option<Cursor>,
+        per_page: Option<i64>,
+        language: Language,
+        match_type: Option<&str>,
+        count: bool,
+    ) -> Result<CursorPage<Rating>, Error>;
     /// Returns the ratings for an event (in a specific language), page by page
     fn get_ratings_for_event(
         &self,
         event_id: i32,
         page: i64,
+        per_page: Option<i64>,
         language: Language,
+        sort: RatingSort,
+        order: Order,
     ) -> Result<Page<Rating>, Error>;
     /// Returns the ratings for a match (in a specific language), page by page
     fn get_ratings_for_match(
         &self,
         match_id: i32,
         page: i64,
+        per_page: Option<i64>,
         language: Language,
+        sort: RatingSort,
+        order: Order,
     ) -> Result<Page<Rating>, Error>;
+    /// Searches the events, matches, match descriptions and published ratings, page by page. The
+    /// descriptions and ratings are only searched in the given language.
+    fn search(&self, query: &str, language: Language, page: i64) -> Result<SearchResults, Error>;
     /// Creates a new rating in the database
     fn new_rating(&self, rating: NewRating) -> Result<(), Error>;
+    /// Returns a rating
+    fn get_rating_by(&self, rating_id: i32) -> Result<Rating, Error>;
+    /// Returns the ratings of an account, even the hidden ones, the latest first, page by page
+    fn get_ratings_for_user(&self, user_id: i32, page: i64) -> Result<Page<Rating>, Error>;
+    /// Changes only the given fields of a rating, keeping its previous version as a revision
+    fn update_rating(
+        &self,
+        rating_id: i32,
+        changes: RatingChanges,
+        editor: &User,
+    ) -> Result<Rating, Error>;
+    /// Deletes a rating, along with its revisions
+    fn delete_rating(&self, rating_id: i32) -> Result<(), Error>;
+    /// Returns the previous versions of a rating, the latest first
+    fn get_rating_revisions(&self, rating_id: i32) -> Result<Vec<RatingRevision>, Error>;
+    /// Flags a rating for the moderators, once per account
+    fn new_rating_flag(&self, flag: NewRatingFlag) -> Result<RatingFlag, Error>;
+    /// Returns the flags of a rating, the latest first
+    fn get_rating_flags(&self, rating_id: i32) -> Result<Vec<RatingFlag>, Error>;
+    /// Returns the ratings with unresolved flags, the most flagged first, page by page
+    fn get_flagged_ratings(&self, page: i64) -> Result<Page<FlaggedRating>, Error>;
+    /// Hides, restores or deletes a rating and records it in the audit trail, in one
+    /// transaction. Hiding or restoring resolves the pending flags of the rating.
+    fn moderate_rating(
+        &self,
+        rating_id: i32,
+        action: ModerationAction,
+        moderator: &User,
+        reason: Option<String>,
+    ) -> Result<ModerationLog, Error>;
+    /// Returns the audit trail of the moderation, the latest first, page by page
+    fn get_moderation_log(&self, page: i64) -> Result<Page<ModerationLog>, Error>;
+    /// Returns the promotions by name, page by page
+    fn get_promotions(&self, page: i64) -> Result<Page<Promotion>, Error>;
+    /// Returns information about a promotion
+    fn get_promotion_by(&self, promotion_id: i32) -> Result<Promotion, Error>;
+    /// Returns the events of a promotion, the latest first, page by page
+    fn get_events_for_promotion(&self, promotion_id: i32, page: i64) -> Result<Page<Event>, Error>;
+    /// Returns the average score for a promotion
+    fn get_average_rating_for_promotion(
+        &self,
+        promotion_id: i32,
+    ) -> Result<Option<BigDecimal>, Error>;
+    /// Creates a new promotion in the database
+    fn new_promotion(&self, promotion: NewPromotion) -> Result<Promotion, Error>;
+    /// Replaces every field of a promotion
+    fn update_promotion(
+        &self,
+        promotion_id: i32,
+        promotion: NewPromotion,
+    ) -> Result<Promotion, Error>;
+    /// Moves the events, championships and series of a promotion to another one, then deletes it.
+    /// The kept promotion doesn't change, so it can take the abbreviation of the deleted one.
+    fn merge_promotions(&self, promotion_id: i32, merged_id: i32) -> Result<Promotion, Error>;
+    /// Returns the event series by name, page by page
+    fn get_series(&self, page: i64) -> Result<Page<EventSeries>, Error>;
+    /// Returns information about an event series
+    fn get_series_by(&self, series_id: i32) -> Result<EventSeries, Error>;
+    /// Returns the editions of an event series, the first one first
+    fn get_events_for_series(&self, series_id: i32) -> Result<Vec<Event>, Error>;
+    /// Creates a new event series in the database
+    fn new_series(&self, series: NewEventSeries) -> Result<EventSeries, Error>;
+    /// Replaces every field of an event series
+    fn update_series(&self, series_id: i32, series: NewEventSeries) -> Result<EventSeries, Error>;
+    /// Returns the rated match of an event with the best average score
+    fn get_best_match_for_event(&self, event_id: i32) -> Result<Option<RatedMatch>, Error>;
+    /// Returns the venues by name, page by page
+    fn get_venues(&self, page: i64) -> Result<Page<Venue>, Error>;
+    /// Returns the venues with published ratings, the best average score first, page by page
+    fn get_best_venues(&self, page: i64) -> Result<Page<RatedVenue>, Error>;
+    /// Returns information about a venue
+    fn get_venue_by(&self, venue_id: i32) -> Result<Venue, Error>;
+    /// Returns the events held at a venue with their average score, the latest first, page by
+    /// page
+    fn get_events_for_venue(&self, venue_id: i32, page: i64) -> Result<Page<RatedEvent>, Error>;
+    /// Creates a new venue in the database
+    fn new_venue(&self, venue: NewVenue) -> Result<Venue, Error>;
+    /// Replaces every field of a venue
+    fn update_venue(&self, venue_id: i32, venue: NewVenue) -> Result<Venue, Error>;
+    /// Returns the languages that can be used, by code
+    fn get_languages(&self) -> Result<Vec<LanguageInfo>, Error>;
+    /// Returns information about a language
+    fn get_language_by(&self, code: Language) -> Result<LanguageInfo, Error>;
+    /// Adds a language that can be used from now on
+    fn new_language(&self, language: LanguageInfo) -> Result<LanguageInfo, Error>;
+    /// Returns the match types by code, with their labels in a specific language
+    fn get_match_types(&self, language: Language) -> Result<Vec<LocalizedMatchType>, Error>;
+    /// Returns the types of a match, with their labels in a specific language
+    fn get_types_for_match(
+        &self,
+        match_id: i32,
+        language: Language,
+    ) -> Result<Vec<LocalizedMatchType>, Error>;
+    /// Creates a new match type and its localized labels, in one transaction
+    fn new_match_type(
+        &self,
+        match_type: NewMatchType,
+        labels: Vec<LocalizedLabel>,
+    ) -> Result<MatchType, Error>;
+    /// Replaces the types of a match, in one transaction
+    fn set_match_types(&self, match_id: i32, match_type_ids: Vec<i32>) -> Result<(), Error>;
+    /// Returns the descriptions in a language of the matches without any type
+    fn get_unclassified_descriptions(&self, language: Language) -> Result<Vec<MatchDesc>, Error>;
+    /// Returns the average score for the matches of a type
+    fn get_average_rating_for_match_type(
+        &self,
+        match_type_id: i32,
+    ) -> Result<Option<BigDecimal>, Error>;
+    /// Returns the championships by name, page by page
+    fn get_championships(&self, page: i64) -> Result<Page<Championship>, Error>;
+    /// Returns information about a championship
+    fn get_championship_by(&self, championship_id: i32) -> Result<Championship, Error>;
+    /// Returns every reign of a championship with the ratings of the defenses
+    fn get_title_history(&self, championship_id: i32) -> Result<TitleHistory, Error>;
+    /// Creates a new championship in the database
+    fn new_championship(&self, championship: NewChampionship) -> Result<Championship, Error>;
+    /// Replaces every field of a championship
+    fn update_championship(
+        &self,
+        championship_id: i32,
+        championship: NewChampionship,
+    ) -> Result<Championship, Error>;
+    /// Adds a reign to the history of a championship
+    fn new_title_reign(&self, reign: NewTitleReign) -> Result<TitleReign, Error>;
+    /// Removes a reign from the history of a championship
+    fn delete_title_reign(&self, championship_id: i32, reign_id: i32) -> Result<(), Error>;
+    /// Returns the championships contested in a match
+    fn get_contested_titles(&self, match_id: i32) -> Result<Vec<ContestedTitle>, Error>;
+    /// Marks a match as contested for a championship, or changes whether the title changed hands
+    fn set_title_match(&self, title_match: NewTitleMatch) -> Result<(), Error>;
+    /// Removes a championship from the ones contested in a match
+    fn delete_title_match(&self, match_id: i32, championship_id: i32) -> Result<(), Error>;
     /// Returns the average score for an event
     fn get_average_rating_for_event(&self, event_id: i32) -> Result<Option<BigDecimal>, Error>;
     /// Returns the average score for a match
     fn get_average_rating_for_match(&self, match_id: i32) -> Result<Option<BigDecimal>, Error>;
+    /// Creates a new event in the database
+    fn new_event(&self, event: NewEvent) -> Result<Event, Error>;
+    /// Replaces every field of an event
+    fn update_event(&self, event_id: i32, event: NewEvent) -> Result<Event, Error>;
+    /// Changes only the given fields of an event
+    fn patch_event(&self, event_id: i32, changes: EventChanges) -> Result<Event, Error>;
+    /// Deletes an event, along with its matches and their ratings
+    fn delete_event(&self, event_id: i32) -> Result<(), Error>;
+    /// Creates a new match, its participants and its localized descriptions, in one transaction
+    fn new_match(
+        &self,
+        match_obj: NewMatch,
+        descriptions: Vec<LocalizedDescription>,
+    ) -> Result<MatchDetails, Error>;
+    /// Replaces every field of a match, and its participants
+    fn update_match(&self, match_id: i32, match_obj: NewMatch) -> Result<MatchDetails, Error>;
+    /// Changes only the given fields of a match, and its participants if the workers changed
+    fn patch_match(&self, match_id: i32, changes: MatchChanges) -> Result<MatchDetails, Error>;
+    /// Puts the matches of a card in a new order, in one transaction. Every match of the card
+    /// must be given once.
+    fn reorder_card(&self, event_id: i32, match_ids: Vec<i32>) -> Result<Vec<MatchDetails>, Error>;
+    /// Sets or clears the result of a match
+    fn set_match_result(&self, match_id: i32, result: MatchResult) -> Result<MatchDetails, Error>;
+    /// Gives another name to a worker. A worker already known under this name is merged into
+    /// this one, in one transaction.
+    fn new_worker_alias(&self, alias: NewWorkerAlias) -> Result<WorkerAlias, Error>;
+    /// Returns the other names of a worker
+    fn get_worker_aliases(&self, worker_id: i32) -> Result<Vec<WorkerAlias>, Error>;
+    /// Deletes another name of a worker
+    fn delete_worker_alias(&self, worker_id: i32, alias_id: i32) -> Result<(), Error>;
+    /// Returns the worker with this name or alias, whatever its case
+    fn get_worker_by_name(&self, name: &str) -> Result<Worker, Error>;
+    /// Returns the matches of a worker, the latest first, page by page
+    fn get_matches_for_worker(
+        &self,
+        worker_id: i32,
+        page: i64,
+    ) -> Result<Page<MatchDetails>, Error>;
+    /// Returns the number of matches of a worker
+    fn get_match_count_for_worker(&self, worker_id: i32) -> Result<i64, Error>;
+    /// Returns the number of ratings received by the matches of a worker
+    fn get_rating_count_for_worker(&self, worker_id: i32) -> Result<i64, Error>;
+    /// Returns the average score of the matches of a worker
+    fn get_average_rating_for_worker(&self, worker_id: i32) -> Result<Option<BigDecimal>, Error>;
+    /// Returns the rated match of a worker with the best average score
+    fn get_best_match_for_worker(&self, worker_id: i32) -> Result<Option<RatedMatch>, Error>;
+    /// Returns the rated match of a worker with the worst average score
+    fn get_worst_match_for_worker(&self, worker_id: i32) -> Result<Option<RatedMatch>, Error>;
+    /// Deletes a match, along with its descriptions and ratings
+    fn delete_match(&self, match_id: i32) -> Result<(), Error>;
+    /// Creates a localized description for a match
+    fn new_match_description(&self, description: NewMatchDesc) -> Result<MatchDesc, Error>;
+    /// Replaces the text of a localized description
+    fn update_match_description(
+        &self,
+        match_id: i32,
+        language: Language,
+        description: String,
+    ) -> Result<MatchDesc, Error>;
+    /// Deletes a localized description
+    fn delete_match_description(&self, match_id: i32, language: Language) -> Result<(), Error>;
+    /// Creates or updates the events, matches and descriptions of the cards, in one transaction.
+    /// With `dry_run`, everything is rolled back and only the report is kept.
+    fn import_cards(&self, cards: Vec<ImportCard>, dry_run: bool) -> Result<ImportReport, Error>;
+    /// Gives every card matching the filter to `write`, with its descriptions and ratings, one
+    /// event at a time and in date order
+    fn export_cards(
+        &self,
+        filter: &ExportFilter,
+        write: &mut dyn FnMut(ImportCard) -> Result<(), Error>,
+    ) -> Result<(), Error>;
+    /// Creates a new account
+    fn new_user(&self, user: NewUser) -> Result<User, Error>;
+    /// Returns the account with this username, whatever its case
+    fn get_user_by_username(&self, username: &str) -> Result<User, Error>;
+    /// Creates a new session for an account
+    fn new_session(&self, session: NewSession) -> Result<(), Error>;
+    /// Returns the account owning an unexpired session
+    fn get_user_by_session(&self, token_hash: &str) -> Result<User, Error>;
+    /// Ends a session
+    fn delete_session(&self, token_hash: &str) -> Result<(), Error>;
+    /// Records the request of an account for the anonymous ratings of a username
+    fn new_rating_claim(&self, claim: NewRatingClaim) -> Result<RatingClaim, Error>;
+    /// Returns the claims waiting for a moderator, the oldest first, page by page
+    fn get_pending_rating_claims(&self, page: i64) -> Result<Page<RatingClaim>, Error>;
+    /// Approves or rejects a pending claim, in one transaction. Once approved, the anonymous
+    /// ratings made with the claimed username are attributed to the account, but on the matches
+    /// the account already rated.
+    fn resolve_rating_claim(
+        &self,
+        claim_id: i32,
+        approved: bool,
+        moderator: &User,
+    ) -> Result<RatingClaim, Error>;
+    /// Returns the accounts, page by page
+    fn get_users(&self, page: i64) -> Result<Page<User>, Error>;
+    /// Returns an account
+    fn get_user_by(&self, user_id: i32) -> Result<User, Error>;
+    /// Changes the role of an account. The last administrator can't lose its role.
+    fn set_user_role(&self, user_id: i32, role: Role) -> Result<User, Error>;
+    /// Returns whether there is at least one administrator
+    fn has_admin(&self) -> Result<bool, Error>;
+}
+
+/// Returns the worker known under this name or alias, whatever its case, creating it if needed
+fn find_or_create_worker(connection: &mut PgConnection, name: &str) -> Result<i32, Error> {
+    let by_name = worker::table
+        .filter(lower(worker::name).eq(name.to_lowercase()))
+        .select(worker::id)
+        .first::<i32>(connection)
+        .optional()?;
+    if let Some(worker_id) = by_name {
+        return Ok(worker_id);
+    }
+
+    let by_alias = worker_alias::table
+        .filter(lower(worker_alias::name).eq(name.to_lowercase()))
+        .select(worker_alias::worker_id)
+        .first::<i32>(connection)
+        .optional()?;
+    if let Some(worker_id) = by_alias {
+        return Ok(worker_id);
+    }
+
+    Ok(NewWorker {
+        name: name.to_string(),
+    }
+    .insert_into(worker::table)
+    .returning(worker::id)
+    .get_result::<i32>(connection)?)
+}
+
+/// Replaces the participants of a match by the ones parsed from its workers
+fn set_participants(
+    connection: &mut PgConnection,
+    match_id: i32,
+    workers: &str,
+) -> Result<(), Error> {
+    let mut fields = Vec::new();
+    workers::check_workers(workers, "workers", &mut fields);
+    if !fields.is_empty() {
+        return Err(Error::InvalidData { fields });
+    }
+
+    diesel::delete(match_participant::table.filter(match_participant::match_id.eq(match_id)))
+        .execute(connection)?;
+
+    for (index, names) in workers::parse_sides(workers).into_iter().enumerate() {
+        for name in names {
+            let worker_id = find_or_create_worker(connection, &name)?;
+            // a worker written twice in the same match only takes part once
+            NewMatchParticipant {
+                match_id,
+                worker_id,
+                side: index as i16 + 1,
+            }
+            .insert_into(match_participant::table)
+            .on_conflict((match_participant::match_id, match_participant::worker_id))
+            .do_nothing()
+            .execute(connection)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Loads the participants of the matches, keeping the matches in order
+fn with_participants(
+    connection: &mut PgConnection,
+    matches: Vec<Match>,
+) -> Result<Vec<MatchDetails>, Error> {
+    let match_ids: Vec<i32> = matches.iter().map(|match_obj| match_obj.id()).collect();
+    let mut participants = match_participant::table
+        .inner_join(worker::table)
+        .filter(match_participant::match_id.eq_any(&match_ids))
+        .order_by((match_participant::side.asc(), match_participant::id.asc()))
+        .select((
+            match_participant::match_id,
+            match_participant::side,
+            worker::id,
+            worker::name,
+        ))
+        .load::<Participant>(connection)?;
+
+    Ok(matches
+        .into_iter()
+        .map(|match_obj| {
+            let (own, others) = participants
+                .drain(..)
+                .partition(|participant| participant.match_id() == match_obj.id());
+            participants = others;
+            MatchDetails {
+                match_obj,
+                participants: own,
+            }
+        })
+        .collect())
+}
+
+/// Columns of `Event`, the name of the promotion coming from the joined promotion table
+type EventColumns = (
+    event::id,
+    event::name,
+    promotion::name,
+    event::date,
+    event::promotion_id,
+    event::venue_id,
+    event::attendance,
+    event::series_id,
+);
+const EVENT_COLUMNS: EventColumns = (
+    event::id,
+    event::name,
+    promotion::name,
+    event::date,
+    event::promotion_id,
+    event::venue_id,
+    event::attendance,
+    event::series_id,
+);
+// the events grouped to compute their averages are also grouped by their promotion
+diesel::allow_columns_to_appear_in_same_group_by_clause!(
+    event::id,
+    event::name,
+    event::date,
+    event::promotion_id,
+    event::venue_id,
+    event::attendance,
+    event::series_id,
+    promotion::id,
+    promotion::name,
+);
+
+/// Ids of the matches of a type, to filter other queries with
+type MatchesOfType = dsl::Select<
+    dsl::Filter<
+        dsl::InnerJoin<match_classification::table, match_type::table>,
+        dsl::Eq<match_type::code, String>,
+    >,
+    match_classification::match_id,
+>;
+
+/// Returns the position after the last match of a card. The event is locked until the end of
+/// the transaction, so two matches added at the same time don't get the same position.
+fn next_position(connection: &mut PgConnection, event_id: i32) -> Result<i16, Error> {
+    event::table
+        .find(event_id)
+        .select(event::id)
+        .for_update()
+        .first::<i32>(connection)
+        .optional()?
+        .ok_or_else(|| Error::unknown_reference("event_id"))?;
+
+    let last_position = match_::table
+        .filter(match_::event_id.eq(event_id))
+        .select(dsl::max(match_::position))
+        .get_result::<Option<i16>>(connection)?;
+    Ok(last_position.unwrap_or(0) + 1)
+}
+
+/// Returns the position at the end of the card of an event, if a match moves to this event
+fn position_on_new_card(
+    connection: &mut PgConnection,
+    match_id: i32,
+    event_id: i32,
+) -> Result<Option<i16>, Error> {
+    let current_event_id = match_::table
+        .find(match_id)
+        .select(match_::event_id)
+        .first::<i32>(connection)?;
+
+    if current_event_id == event_id {
+        Ok(None)
+    } else {
+        Ok(Some(next_position(connection, event_id)?))
+    }
+}
+
+/// Returns the ids of the matches of a type, given by its code
+fn matches_of_type(code: &str) -> MatchesOfType {
+    match_classification::table
+        .inner_join(match_type::table)
+        .filter(match_type::code.eq(code.to_string()))
+        .select(match_classification::match_id)
+}
+
+/// Returns the published ratings in a language, optionally only the ones of matches of a type
+fn published_ratings<'a>(
+    language: Language,
+    match_type: Option<&str>,
+) -> rating::BoxedQuery<'a, Pg> {
+    let mut ratings = rating::table
+        .filter(rating::language_code.eq(language))
+        .filter(rating::hidden.eq(false))
+        .into_boxed();
+    if let Some(match_type) = match_type {
+        ratings = ratings.filter(rating::match_id.eq_any(matches_of_type(match_type)));
+    }
+    ratings
+}
+
+/// Returns the event with the name of its promotion
+fn load_event(connection: &mut PgConnection, event_id: i32) -> QueryResult<Event> {
+    event::table
+        .inner_join(promotion::table)
+        .filter(event::id.eq(event_id))
+        .select(EVENT_COLUMNS)
+        .first::<Event>(connection)
+}
+
+/// Returns the id of the promotion with this name or abbreviation, whatever its case. A name
+/// can't be the abbreviation of another promotion, so there is at most one.
+fn find_promotion(connection: &mut PgConnection, name: &str) -> QueryResult<Option<i32>> {
+    let name = name.trim().to_lowercase();
+    promotion::table
+        .filter(
+            lower(promotion::name)
+                .eq(name.clone())
+                .nullable()
+                .or(lower_nullable(promotion::abbreviation).eq(name)),
+        )
+        .select(promotion::id)
+        .first::<i32>(connection)
+        .optional()
+}
+
+/// Refuses a promotion whose name is the abbreviation of another promotion, or whose
+/// abbreviation is the name of another one, as events find their promotion by both
+fn check_promotion_names(
+    connection: &mut PgConnection,
+    promotion: &NewPromotion,
+    promotion_id: Option<i32>,
+) -> Result<(), Error> {
+    let mut fields = Vec::new();
+
+    let name = promotion.name.trim().to_lowercase();
+    let mut same_abbreviation = promotion::table
+        .filter(lower_nullable(promotion::abbreviation).eq(name))
+        .into_boxed();
+    if let Some(promotion_id) = promotion_id {
+        same_abbreviation = same_abbreviation.filter(promotion::id.ne(promotion_id));
+    }
+    if dsl::select(dsl::exists(same_abbreviation)).get_result::<bool>(connection)? {
+        fields.push(FieldError::new(
+            "name",
+            "abbreviation_of_another",
+            "This name is the abbreviation of another promotion, merge them instead.",
+        ));
+    }
+
+    if let Some(abbreviation) = &promotion.abbreviation {
+        let abbreviation = abbreviation.trim().to_lowercase();
+        let mut same_name = promotion::table
+            .filter(lower(promotion::name).eq(abbreviation))
+            .into_boxed();
+        if let Some(promotion_id) = promotion_id {
+            same_name = same_name.filter(promotion::id.ne(promotion_id));
+        }
+        if dsl::select(dsl::exists(same_name)).get_result::<bool>(connection)? {
+            fields.push(FieldError::new(
+                "abbreviation",
+                "name_of_another",
+                "This abbreviation is the name of another promotion, merge them instead.",
+            ));
+        }
+    }
+
+    if fields.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::InvalidData { fields })
+    }
+}
+
+/// Returns the number of defenses during a reign, and their average score. The defenses are the
+/// title matches where the title didn't change hands, on a card from the start of the reign and
+/// before its end. The end is left out as it is the start of the next reign, which would count
+/// the same defense twice.
+fn get_defense_stats(
+    connection: &mut PgConnection,
+    reign: &TitleReign,
+    championship_id: i32,
+) -> Result<(i64, Option<BigDecimal>), Error> {
+    let mut defenses = title_match::table
+        .inner_join(match_::table.inner_join(event::table))
+        .filter(title_match::championship_id.eq(championship_id))
+        .filter(title_match::title_changed.eq(false))
+        .filter(event::date.ge(*reign.start_date()))
+        .into_boxed();
+    let mut ratings = title_match::table
+        .inner_join(
+            match_::table
+                .inner_join(event::table)
+                .inner_join(rating::table),
+        )
+        .filter(title_match::championship_id.eq(championship_id))
+        .filter(title_match::title_changed.eq(false))
+        .filter(event::date.ge(*reign.start_date()))
+        .filter(rating::hidden.eq(false))
+        .into_boxed();
+    if let Some(end_date) = reign.end_date() {
+        defenses = defenses.filter(event::date.lt(*end_date));
+        ratings = ratings.filter(event::date.lt(*end_date));
+    }
+
+    let defense_count = defenses.count().get_result::<i64>(connection)?;
+    let average_score = ratings
+        .select(dsl::avg(rating::score))
+        .get_result::<Option<BigDecimal>>(connection)?;
+    Ok((defense_count, average_score))
+}
+
+/// Returns the rated match of a worker with the best or the worst average score. Between
+/// matches with the same average, the one with the most ratings wins.
+fn get_extreme_match_for_worker(
+    connection: &mut PgConnection,
+    worker_id: i32,
+    best: bool,
+) -> Result<Option<RatedMatch>, Error> {
+    let matches = match_::table
+        .inner_join(match_participant::table)
+        .inner_join(rating::table)
+        .filter(match_participant::worker_id.eq(worker_id))
+        .filter(rating::hidden.eq(false))
+        .group_by(match_::id)
+        .select((
+            match_::all_columns,
+            dsl::avg(rating::score),
+            dsl::count(rating::id),
+        ))
+        .into_boxed();
+
+    let matches = if best {
+        matches.order_by((
+            dsl::avg(rating::score).desc(),
+            dsl::count(rating::id).desc(),
+            match_::id.asc(),
+        ))
+    } else {
+        matches.order_by((
+            dsl::avg(rating::score).asc(),
+            dsl::count(rating::id).desc(),
+            match_::id.asc(),
+        ))
+    };
+
+    Ok(matches.first::<RatedMatch>(connection).optional()?)
+}
+
+/// Imports one card. Events are recognized by their name and promotion (`UQ_Name_Promotion`),
+/// matches by their workers on the event and ratings by their username on the match
+/// (`UQ_Rating_Match_Username`), so importing the same card twice changes nothing.
+fn import_card(
+    connection: &mut PgConnection,
+    index: usize,
+    card: ImportCard,
+) -> Result<ImportedEvent, Error> {
+    let mut report = ImportedEvent {
+        name: card.event.name.clone(),
+        promotion: card.event.promotion.clone(),
+        ..Default::default()
+    };
+
+    // promotions are only created by the editors, a typo must not add one
+    let promotion_id = find_promotion(connection, &card.event.promotion)?
+        .ok_or_else(|| Error::unknown_reference(&format!("cards[{}].event.promotion", index)))?;
+
+    let existing_event = event::table
+        .filter(event::name.eq(&card.event.name))
+        .filter(event::promotion_id.eq(promotion_id))
+        .select(event::id)
+        .first::<i32>(connection)
+        .optional()?;
+
+    let event_id = match existing_event {
+        Some(event_id) => {
+            diesel::update(event::table.find(event_id))
+                .set(event::date.eq(card.event.date))
+                .execute(connection)?;
+            event_id
+        }
+        None => {
+            report.created = true;
+            NewEvent {
+                name: card.event.name,
+                promotion_id,
+                date: card.event.date,
+                venue_id: None,
+                attendance: None,
+                series_id: None,
+            }
+            .insert_into(event::table)
+            .returning(event::id)
+            .get_result::<i32>(connection)?
+        }
+    };
+    report.id = Some(event_id);
+
+    for match_obj in card.matches {
+        let existing_match = match_::table
+            .filter(match_::event_id.eq(event_id))
+            .filter(match_::workers.eq(&match_obj.workers))
+            .select(match_::id)
+            .first::<i32>(connection)
+            .optional()?;
+
+        let match_id = match existing_match {
+            Some(match_id) => {
+                report.matches_existing += 1;
+                match_id
+            }
+            None => {
+                report.matches_created += 1;
+                let position = next_position(connection, event_id)?;
+                let match_id = diesel::insert_into(match_::table)
+                    .values((
+                        NewMatch {
+                            event_id,
+                            workers: match_obj.workers.clone(),
+                            segment: Segment::default(),
+                        },
+                        match_::position.eq(position),
+                    ))
+                    .returning(match_::id)
+                    .get_result::<i32>(connection)?;
+                set_participants(connection, match_id, &match_obj.workers)?;
+                match_id
+            }
+        };
+
+        for description in match_obj.descriptions {
+            let updated = diesel::update(
+                match_desc::table
+                    .filter(match_desc::match_id.eq(match_id))
+                    .filter(match_desc::language_code.eq(description.language_code)),
+            )
+            .set(match_desc::description.eq(&description.description))
+            .execute(connection)?;
+
+            if updated == 0 {
+                NewMatchDesc {
+                    match_id,
+                    description: description.description,
+                    language_code: description.language_code,
+                }
+                .insert_into(match_desc::table)
+                .execute(connection)?;
+                report.descriptions_created += 1;
+            } else {
+                report.descriptions_updated += 1;
+            }
+        }
+
+        for rating in match_obj.ratings {
+            let user_id = match &rating.account {
+                Some(account) => users::table
+                    .filter(lower(users::username).eq(account.to_lowercase()))
+                    .select(users::id)
+                    .first::<i32>(connection)
+                    .optional()?,
+                None => None,
+            };
+
+            let created = NewRating {
+                match_id,
+                language_code: rating.language_code,
+                username: rating.username,
+                score: rating.score,
+                publication_date: rating.publication_date,
+                opinion: rating.opinion,
+                user_id,
+            }
+            .insert_into(rating::table)
+            .on_conflict_do_nothing()
+            .execute(connection)?;
+
+            if created == 0 {
+                report.ratings_existing += 1;
+            } else {
+                report.ratings_created += 1;
+                if rating.account.is_some() && user_id.is_none() {
+                    report.ratings_without_account += 1;
+                }
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+fn import_all(
+    connection: &mut PgConnection,
+    cards: Vec<ImportCard>,
+) -> Result<Vec<ImportedEvent>, Error> {
+    cards
+        .into_iter()
+        .enumerate()
+        .map(|(index, card)| import_card(connection, index, card))
+        .collect()
+}
+
+/// Searches every kind of item, each kind being paginated on its own. A kind can run out of
+/// items before the others, its page is then empty.
+fn search_all(
+    connection: &mut PgConnection,
+    query: &str,
+    language: Language,
+    page: i64,
+) -> Result<SearchResults, Error> {
+    // the documents are the expressions of the search indexes
+    let names_query = websearch_to_tsquery(simple_config(), query);
+    let event_document = to_tsvector(
+        simple_config(),
+        event::name.concat(" ").concat(promotion::name),
+    );
+    let events = event::table
+        .inner_join(promotion::table)
+        .filter(TsMatches::new(event_document.clone(), names_query.clone()))
+        .order_by((
+            ts_rank(event_document.clone(), names_query.clone()).desc(),
+            event::id.asc(),
+        ))
+        .select((
+            EVENT_COLUMNS,
+            ts_rank(event_document, names_query.clone()),
+            ts_headline(
+                simple_config(),
+                event::name.concat(" - ").concat(promotion::name),
+                names_query.clone(),
+                SNIPPET_OPTIONS,
+            ),
+        ))
+        .paginate(page)
+        .load_or_count_pages::<EventHit>(connection)?;
+
+    let workers_document = to_tsvector(simple_config(), match_::workers);
+    let matches = match_::table
+        .filter(TsMatches::new(
+            workers_document.clone(),
+            names_query.clone(),
+        ))
+        .order_by((
+            ts_rank(workers_document.clone(), names_query.clone()).desc(),
+            match_::id.asc(),
+        ))
+        .select((
+            match_::all_columns,
+            ts_rank(workers_document, names_query.clone()),
+            ts_headline(
+                simple_config(),
+                match_::workers,
+                names_query,
+                SNIPPET_OPTIONS,
+            ),
+        ))
+        .paginate(page)
+        .load_or_count_pages::<MatchHit>(connection)?;
+
+    let language_query = websearch_to_tsquery(search_config(language), query);
+    let description_document = to_tsvector(
+        search_config(match_desc::language_code),
+        match_desc::description,
+    );
+    let descriptions = match_desc::table
+        .inner_join(match_::table)
+        .filter(match_desc::language_code.eq(language))
+        .filter(TsMatches::new(description_document, language_query))
+        .order_by((
+            ts_rank(description_document, language_query).desc(),
+            match_::id.asc(),
+        ))
+        .select((
+            match_::all_columns,
+            ts_rank(description_document, language_query),
+            ts_headline(
+                search_config(match_desc::language_code),
+                match_desc::description,
+                language_query,
+                SNIPPET_OPTIONS,
+            ),
+        ))
+        .paginate(page)
+        .load_or_count_pages::<MatchHit>(connection)?;
+
+    // ratings without an opinion never match
+    let opinion_document = to_tsvector(
+        search_config(rating::language_code),
+        rating::opinion.assume_not_null(),
+    );
+    match rating::table
+        .filter(rating::language_code.eq(language))
+        .filter(rating::hidden.eq(false))
+        .filter(TsMatches::new(opinion_document, language_query))
+        .order_by((
+            ts_rank(opinion_document, language_query).desc(),
+            rating::id.asc(),
+        ))
+        .select((
+            rating::all_columns,
+            ts_rank(opinion_document, language_query),
+            ts_headline(
+                search_config(rating::language_code),
+                rating::opinion.assume_not_null(),
+                language_query,
+                SNIPPET_OPTIONS,
+            ),
+        ))
+        .paginate(page)
+        .load_or_count_pages::<RatingHit>(connection)
+    {
+        Ok(ratings) => Ok(SearchResults {
+            events,
+            matches,
+            descriptions,
+            ratings,
+        }),
+        Err(err) => Err(err.into()),
+    }
 }
 
 impl DatabaseOperations for Database {
-    fn get_events(&self, page: i64) -> Result<Page<Event>, Error> {
+    fn get_events(
+        &self,
+        page: i64,
+        per_page: Option<i64>,
+        sort: EventSort,
+        order: Order,
+    ) -> Result<Page<Event>, Error> {
         let mut connection = self.get_connection()?;
 
-        match event::table
-            .order_by(event::date.desc())
-            .paginate(page)
-            .per_page(ITEMS_PER_PAGE)
-            .load_and_count_pages::<Event>(&mut connection)
-        {
+        let per_page = per_page.unwrap_or(ITEMS_PER_PAGE);
+        let events = match sort {
+            EventSort::Date => {
+                let events = event::table
+                    .inner_join(promotion::table)
+                    .select(EVENT_COLUMNS)
+                    .into_boxed();
+                let events = match order {
+                    Order::Asc => events.order_by((event::date.asc(), event::id.asc())),
+                    Order::Desc => events.order_by((event::date.desc(), event::id.desc())),
+                };
+                events
+                    .paginate(page)
+                    .per_page(per_page)
+                    .load_and_count_pages::<Event>(&mut connection)
+            }
+            EventSort::Average => {
+                // events without any published rating are listed too, after the others
+                let events = event::table
+                    .inner_join(promotion::table)
+                    .left_join(
+                        match_::table.left_join(
+                            rating::table.on(rating::match_id
+                                .eq(match_::id)
+                                .and(rating::hidden.eq(false))),
+                        ),
+                    )
+                    .group_by((event::id, promotion::id))
+                    .select(EVENT_COLUMNS)
+                    .into_boxed();
+                let average = dsl::avg(rating::score.nullable());
+                let events = match order {
+                    Order::Asc => events.order_by((average.asc().nulls_last(), event::id.asc())),
+                    Order::Desc => events.order_by((average.desc().nulls_last(), event::id.desc())),
+                };
+                events
+                    .paginate(page)
+                    .per_page(per_page)
+                    .load_and_count_pages::<Event>(&mut connection)
+            }
+        };
+
+        match events {
             Ok(page) => Ok(page),
             Err(err) => Err(err.into()),
         }
@@ -118,10 +1141,7 @@ impl DatabaseOperations for Database {
     fn get_event_by(&self, event_id: i32) -> Result<Event, Error> {
         let mut connection = self.get_connection()?;
 
-        match event::table
-            .filter(event::id.eq(event_id))
-            .first::<Event>(&mut connection)
-        {
+        match load_event(&mut connection, event_id) {
             Ok(event) => Ok(event),
             Err(err) => Err(err.into()),
         }
@@ -139,38 +1159,65 @@ impl DatabaseOperations for Database {
         }
     }
 
-    fn get_match_description(&self, match_id: i32, language: Language) -> Result<MatchDesc, Error> {
+    fn get_match_descriptions(&self, match_id: i32) -> Result<Vec<MatchDesc>, Error> {
         let mut connection = self.get_connection()?;
 
         match match_desc::table
             .filter(match_desc::match_id.eq(match_id))
-            .filter(match_desc::language_code.eq(language))
-            .first::<MatchDesc>(&mut connection)
+            .order_by(match_desc::language_code.asc())
+            .load::<MatchDesc>(&mut connection)
         {
-            Ok(matchdesc) => Ok(matchdesc),
+            Ok(descriptions) => Ok(descriptions),
             Err(err) => Err(err.into()),
         }
     }
 
-    fn get_card(&self, event_id: i32) -> Result<Vec<Match>, Error> {
+    fn get_match_details(&self, match_id: i32) -> Result<MatchDetails, Error> {
         let mut connection = self.get_connection()?;
 
-        match match_::table
+        let match_obj = match_::table
+            .find(match_id)
+            .first::<Match>(&mut connection)?;
+        let mut details = with_participants(&mut connection, vec![match_obj])?;
+        match details.pop() {
+            Some(details) => Ok(details),
+            None => Err(Error::ResourceDoesNotExists),
+        }
+    }
+
+    fn get_card(
+        &self,
+        event_id: i32,
+        match_type: Option<&str>,
+    ) -> Result<Vec<MatchDetails>, Error> {
+        let mut connection = self.get_connection()?;
+
+        let mut card = match_::table
             .filter(match_::event_id.eq(event_id))
-            .load::<Match>(&mut connection)
-        {
-            Ok(card) => Ok(card),
-            Err(err) => Err(err.into()),
+            .order_by(match_::position.asc())
+            .into_boxed();
+        if let Some(match_type) = match_type {
+            card = card.filter(match_::id.eq_any(matches_of_type(match_type)));
         }
+
+        let card = card.load::<Match>(&mut connection)?;
+        with_participants(&mut connection, card)
     }
 
-    fn get_ratings(&self, page: i64, language: Language) -> Result<Page<Rating>, Error> {
+    fn get_ratings(
+        &self,
+        page: i64,
+        per_page: Option<i64>,
+        language: Language,
+        match_type: Option<&str>,
+        sort: RatingSort,
+        order: Order,
+    ) -> Result<Page<Rating>, Error> {
         let mut connection = self.get_connection()?;
 
-        match rating::table
-            .filter(rating::language_code.eq(language))
-            .order_by(rating::publication_date.desc())
+        match sort_ratings(published_ratings(language, match_type), sort, order)
             .paginate(page)
+            .per_page(per_page.unwrap_or(DEFAULT_PER_PAGE))
             .load_and_count_pages::<Rating>(&mut connection)
         {
             Ok(page) => Ok(page),
@@ -178,22 +1225,102 @@ impl DatabaseOperations for Database {
         }
     }
 
+    fn get_ratings_feed(
+        &self,
+        cursor: Option<Cursor>,
+        per_page: Option<i64>,
+        language: Language,
+        match_type: Option<&str>,
+        count: bool,
+    ) -> Result<CursorPage<Rating>, Error> {
+        let mut connection = self.get_connection()?;
+
+        let total_items = if count {
+            Some(
+                published_ratings(language, match_type)
+                    .count()
+                    .get_result::<i64>(&mut connection)?,
+            )
+        } else {
+            None
+        };
+
+        let ratings = published_ratings(language, match_type);
+        // the previous page is read backwards, from the cursor
+        let ratings = match cursor {
+            None => ratings.order_by((rating::publication_date.desc(), rating::id.desc())),
+            Some(Cursor::After(date, id)) => ratings
+                .filter(
+                    rating::publication_date
+                        .lt(date)
+                        .or(rating::publication_date.eq(date).and(rating::id.lt(id))),
+                )
+                .order_by((rating::publication_date.desc(), rating::id.desc())),
+            Some(Cursor::Before(date, id)) => ratings
+                .filter(
+                    rating::publication_date
+                        .gt(date)
+                        .or(rating::publication_date.eq(date).and(rating::id.gt(id))),
+                )
+                .order_by((rating::publication_date.asc(), rating::id.asc())),
+        };
+
+        // one more rating tells if there is another page
+        let per_page = per_page.unwrap_or(DEFAULT_PER_PAGE);
+        let mut items = ratings
+            .limit(per_page + 1)
+            .load::<Rating>(&mut connection)?;
+        let has_more = items.len() as i64 > per_page;
+        items.truncate(per_page as usize);
+
+        let (has_previous, has_next) = match cursor {
+            None => (false, has_more),
+            Some(Cursor::After(_, _)) => (true, has_more),
+            Some(Cursor::Before(_, _)) => {
+                items.reverse();
+                (has_more, true)
+            }
+        };
+        let next_cursor = items
+            .last()
+            .filter(|_| has_next)
+            .map(|rating| Cursor::After(*rating.publication_date(), rating.id()).to_string());
+        let prev_cursor = items
+            .first()
+            .filter(|_| has_previous)
+            .map(|rating| Cursor::Before(*rating.publication_date(), rating.id()).to_string());
+
+        Ok(CursorPage {
+            items,
+            next_cursor,
+            prev_cursor,
+            total_items,
+        })
+    }
+
     fn get_ratings_for_event(
         &self,
         event_id: i32,
         page: i64,
+        per_page: Option<i64>,
         language: Language,
+        sort: RatingSort,
+        order: Order,
     ) -> Result<Page<Rating>, Error> {
         let mut connection = self.get_connection()?;
 
-        match rating::table
+        let ratings = rating::table
             .inner_join(match_::table)
             .filter(match_::event_id.eq(event_id))
             .filter(rating::language_code.eq(language))
+            .filter(rating::hidden.eq(false))
             // workaround, not working without (outputs a tuple of rating and id)
             .select(rating::all_columns)
-            .order_by(rating::publication_date.desc())
+            .into_boxed();
+
+        match sort_ratings(ratings, sort, order)
             .paginate(page)
+            .per_page(per_page.unwrap_or(DEFAULT_PER_PAGE))
             .load_and_count_pages::<Rating>(&mut connection)
         {
             Ok(page) => Ok(page),
@@ -205,15 +1332,22 @@ impl DatabaseOperations for Database {
         &self,
         match_id: i32,
         page: i64,
+        per_page: Option<i64>,
         language: Language,
+        sort: RatingSort,
+        order: Order,
     ) -> Result<Page<Rating>, Error> {
         let mut connection = self.get_connection()?;
 
-        match rating::table
+        let ratings = rating::table
             .filter(rating::match_id.eq(match_id))
             .filter(rating::language_code.eq(language))
-            .order_by(rating::publication_date.desc())
+            .filter(rating::hidden.eq(false))
+            .into_boxed();
+
+        match sort_ratings(ratings, sort, order)
             .paginate(page)
+            .per_page(per_page.unwrap_or(DEFAULT_PER_PAGE))
             .load_and_count_pages::<Rating>(&mut connection)
         {
             Ok(page) => Ok(page),
@@ -221,37 +1355,1802 @@ impl DatabaseOperations for Database {
         }
     }
 
+    fn search(&self, query: &str, language: Language, page: i64) -> Result<SearchResults, Error> {
+        let mut connection = self.get_connection()?;
+        search_all(&mut connection, query, language, page)
+    }
+
     fn new_rating(&self, rating: NewRating) -> Result<(), Error> {
         let mut connection = self.get_connection()?;
 
-        rating.insert_into(rating::table).execute(&mut connection)?;
-        Ok(())
+        match rating.insert_into(rating::table).execute(&mut connection) {
+            Ok(_) => Ok(()),
+            // UQ_Rating_Match_User: the account already rated the match
+            Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+                Err(Error::UsernameAlreadyUsed)
+            }
+            Err(err) => Err(err.into()),
+        }
     }
 
-    fn get_average_rating_for_event(&self, event_id: i32) -> Result<Option<BigDecimal>, Error> {
+    fn get_rating_by(&self, rating_id: i32) -> Result<Rating, Error> {
         let mut connection = self.get_connection()?;
 
-        let ratings = match_::table
-            .filter(match_::event_id.eq(event_id))
-            .inner_join(rating::table)
-            .select(avg(rating::score));
+        match rating::table
+            .find(rating_id)
+            .first::<Rating>(&mut connection)
+        {
+            Ok(rating) => Ok(rating),
+            Err(err) => Err(err.into()),
+        }
+    }
 
-        match ratings.get_result(&mut connection) {
-            Ok(avg_score) => Ok(avg_score),
+    fn get_ratings_for_user(&self, user_id: i32, page: i64) -> Result<Page<Rating>, Error> {
+        let mut connection = self.get_connection()?;
+
+        match rating::table
+            .filter(rating::user_id.eq(user_id))
+            .order_by((rating::publication_date.desc(), rating::id.desc()))
+            .paginate(page)
+            .load_and_count_pages::<Rating>(&mut connection)
+        {
+            Ok(page) => Ok(page),
             Err(err) => Err(err.into()),
         }
     }
 
-    fn get_average_rating_for_match(&self, match_id: i32) -> Result<Option<BigDecimal>, Error> {
+    fn update_rating(
+        &self,
+        rating_id: i32,
+        changes: RatingChanges,
+        editor: &User,
+    ) -> Result<Rating, Error> {
         let mut connection = self.get_connection()?;
 
-        let ratings = rating::table
-            .filter(rating::match_id.eq(match_id))
-            .select(dsl::avg(rating::score));
+        connection.transaction(|connection| {
+            // locked so two simultaneous edits can't both save the same previous version
+            let previous = rating::table
+                .find(rating_id)
+                .for_update()
+                .first::<Rating>(connection)?;
 
-        match ratings.first(&mut connection) {
-            Ok(avg_score) => Ok(avg_score),
+            // diesel refuses empty changesets
+            if changes.is_empty() {
+                return Ok(previous);
+            }
+
+            let now = Utc::now().naive_utc();
+            NewRatingRevision {
+                rating_id,
+                score: previous.score().clone(),
+                opinion: previous.opinion().clone(),
+                revision_date: now,
+                editor_id: Some(editor.id()),
+            }
+            .insert_into(rating_revision::table)
+            .execute(connection)?;
+
+            Ok(diesel::update(rating::table.find(rating_id))
+                .set((&changes, rating::edited_at.eq(now)))
+                .get_result::<Rating>(connection)?)
+        })
+    }
+
+    fn delete_rating(&self, rating_id: i32) -> Result<(), Error> {
+        let mut connection = self.get_connection()?;
+
+        match diesel::delete(rating::table.find(rating_id)).execute(&mut connection)? {
+            0 => Err(Error::ResourceDoesNotExists),
+            _ => Ok(()),
+        }
+    }
+
+    fn get_rating_revisions(&self, rating_id: i32) -> Result<Vec<RatingRevision>, Error> {
+        let mut connection = self.get_connection()?;
+
+        match rating_revision::table
+            .filter(rating_revision::rating_id.eq(rating_id))
+            .order_by((
+                rating_revision::revision_date.desc(),
+                rating_revision::id.desc(),
+            ))
+            .load::<RatingRevision>(&mut connection)
+        {
+            Ok(revisions) => Ok(revisions),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn new_rating_flag(&self, flag: NewRatingFlag) -> Result<RatingFlag, Error> {
+        let mut connection = self.get_connection()?;
+
+        match flag
+            .insert_into(rating_flag::table)
+            .get_result::<RatingFlag>(&mut connection)
+        {
+            Ok(flag) => Ok(flag),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_rating_flags(&self, rating_id: i32) -> Result<Vec<RatingFlag>, Error> {
+        let mut connection = self.get_connection()?;
+
+        match rating_flag::table
+            .filter(rating_flag::rating_id.eq(rating_id))
+            .order_by((rating_flag::creation_date.desc(), rating_flag::id.desc()))
+            .load::<RatingFlag>(&mut connection)
+        {
+            Ok(flags) => Ok(flags),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_flagged_ratings(&self, page: i64) -> Result<Page<FlaggedRating>, Error> {
+        let mut connection = self.get_connection()?;
+
+        match rating::table
+            .inner_join(rating_flag::table)
+            .filter(rating_flag::resolved.eq(false))
+            .group_by(rating::id)
+            .select((
+                rating::all_columns,
+                dsl::count(rating_flag::id),
+                dsl::max(rating_flag::creation_date),
+            ))
+            .order_by((
+                dsl::count(rating_flag::id).desc(),
+                dsl::max(rating_flag::creation_date).desc(),
+            ))
+            .paginate(page)
+            .load_and_count_pages::<FlaggedRating>(&mut connection)
+        {
+            Ok(page) => Ok(page),
             Err(err) => Err(err.into()),
         }
     }
+
+    fn moderate_rating(
+        &self,
+        rating_id: i32,
+        action: ModerationAction,
+        moderator: &User,
+        reason: Option<String>,
+    ) -> Result<ModerationLog, Error> {
+        let mut connection = self.get_connection()?;
+
+        connection.transaction(|connection| {
+            rating::table
+                .find(rating_id)
+                .for_update()
+                .first::<Rating>(connection)?;
+
+            match action {
+                ModerationAction::Hide | ModerationAction::Restore => {
+                    diesel::update(rating::table.find(rating_id))
+                        .set(rating::hidden.eq(action == ModerationAction::Hide))
+                        .execute(connection)?;
+                    diesel::update(
+                        rating_flag::table
+                            .filter(rating_flag::rating_id.eq(rating_id))
+                            .filter(rating_flag::resolved.eq(false)),
+                    )
+                    .set(rating_flag::resolved.eq(true))
+                    .execute(connection)?;
+                }
+                // the flags and revisions go along with the rating
+                ModerationAction::Delete => {
+                    diesel::delete(rating::table.find(rating_id)).execute(connection)?;
+                }
+            }
+
+            Ok(NewModerationLog {
+                rating_id,
+                moderator_id: Some(moderator.id()),
+                action,
+                reason,
+                action_date: Utc::now().naive_utc(),
+            }
+            .insert_into(moderation_log::table)
+            .get_result::<ModerationLog>(connection)?)
+        })
+    }
+
+    fn get_moderation_log(&self, page: i64) -> Result<Page<ModerationLog>, Error> {
+        let mut connection = self.get_connection()?;
+
+        match moderation_log::table
+            .order_by((
+                moderation_log::action_date.desc(),
+                moderation_log::id.desc(),
+            ))
+            .paginate(page)
+            .load_and_count_pages::<ModerationLog>(&mut connection)
+        {
+            Ok(page) => Ok(page),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_worker_by_name(&self, name: &str) -> Result<Worker, Error> {
+        let mut connection = self.get_connection()?;
+        let name = workers::normalize_name(name).to_lowercase();
+
+        match worker::table
+            .left_join(worker_alias::table)
+            .filter(
+                lower(worker::name)
+                    .eq(&name)
+                    .or(lower(worker_alias::name).nullable().eq(&name)),
+            )
+            .select(worker::all_columns)
+            .first::<Worker>(&mut connection)
+        {
+            Ok(worker) => Ok(worker),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_matches_for_worker(
+        &self,
+        worker_id: i32,
+        page: i64,
+    ) -> Result<Page<MatchDetails>, Error> {
+        let mut connection = self.get_connection()?;
+
+        let mut matches = match_::table
+            .inner_join(match_participant::table)
+            .inner_join(event::table)
+            .filter(match_participant::worker_id.eq(worker_id))
+            .select(match_::all_columns)
+            .order_by((event::date.desc(), match_::position.desc()))
+            .paginate(page)
+            .load_and_count_pages::<Match>(&mut connection)?;
+
+        let items = with_participants(&mut connection, mem::take(&mut matches.items))?;
+        Ok(matches.with_items(items))
+    }
+
+    fn get_match_count_for_worker(&self, worker_id: i32) -> Result<i64, Error> {
+        let mut connection = self.get_connection()?;
+
+        match match_participant::table
+            .filter(match_participant::worker_id.eq(worker_id))
+            .count()
+            .get_result(&mut connection)
+        {
+            Ok(count) => Ok(count),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_rating_count_for_worker(&self, worker_id: i32) -> Result<i64, Error> {
+        let mut connection = self.get_connection()?;
+
+        match rating::table
+            .inner_join(
+                match_participant::table.on(match_participant::match_id.eq(rating::match_id)),
+            )
+            .filter(match_participant::worker_id.eq(worker_id))
+            .filter(rating::hidden.eq(false))
+            .count()
+            .get_result(&mut connection)
+        {
+            Ok(count) => Ok(count),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_average_rating_for_worker(&self, worker_id: i32) -> Result<Option<BigDecimal>, Error> {
+        let mut connection = self.get_connection()?;
+
+        let ratings = rating::table
+            .inner_join(
+                match_participant::table.on(match_participant::match_id.eq(rating::match_id)),
+            )
+            .filter(match_participant::worker_id.eq(worker_id))
+            .filter(rating::hidden.eq(false))
+            .select(dsl::avg(rating::score));
+
+        match ratings.get_result(&mut connection) {
+            Ok(avg_score) => Ok(avg_score),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_best_match_for_worker(&self, worker_id: i32) -> Result<Option<RatedMatch>, Error> {
+        let mut connection = self.get_connection()?;
+        get_extreme_match_for_worker(&mut connection, worker_id, true)
+    }
+
+    fn get_worst_match_for_worker(&self, worker_id: i32) -> Result<Option<RatedMatch>, Error> {
+        let mut connection = self.get_connection()?;
+        get_extreme_match_for_worker(&mut connection, worker_id, false)
+    }
+
+    fn get_promotions(&self, page: i64) -> Result<Page<Promotion>, Error> {
+        let mut connection = self.get_connection()?;
+
+        match promotion::table
+            .order_by(promotion::name.asc())
+            .paginate(page)
+            .load_and_count_pages::<Promotion>(&mut connection)
+        {
+            Ok(page) => Ok(page),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_promotion_by(&self, promotion_id: i32) -> Result<Promotion, Error> {
+        let mut connection = self.get_connection()?;
+
+        match promotion::table
+            .find(promotion_id)
+            .first::<Promotion>(&mut connection)
+        {
+            Ok(promotion) => Ok(promotion),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_events_for_promotion(&self, promotion_id: i32, page: i64) -> Result<Page<Event>, Error> {
+        let mut connection = self.get_connection()?;
+
+        match event::table
+            .inner_join(promotion::table)
+            .filter(event::promotion_id.eq(promotion_id))
+            .order_by(event::date.desc())
+            .select(EVENT_COLUMNS)
+            .paginate(page)
+            .per_page(ITEMS_PER_PAGE)
+            .load_and_count_pages::<Event>(&mut connection)
+        {
+            Ok(page) => Ok(page),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_average_rating_for_promotion(
+        &self,
+        promotion_id: i32,
+    ) -> Result<Option<BigDecimal>, Error> {
+        let mut connection = self.get_connection()?;
+
+        let ratings = event::table
+            .filter(event::promotion_id.eq(promotion_id))
+            .inner_join(match_::table.inner_join(rating::table))
+            .filter(rating::hidden.eq(false))
+            .select(dsl::avg(rating::score));
+
+        match ratings.get_result(&mut connection) {
+            Ok(avg_score) => Ok(avg_score),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn new_promotion(&self, promotion: NewPromotion) -> Result<Promotion, Error> {
+        let mut connection = self.get_connection()?;
+
+        connection.transaction(|connection| {
+            check_promotion_names(connection, &promotion, None)?;
+            Ok(promotion
+                .insert_into(promotion::table)
+                .get_result::<Promotion>(connection)?)
+        })
+    }
+
+    fn update_promotion(
+        &self,
+        promotion_id: i32,
+        promotion: NewPromotion,
+    ) -> Result<Promotion, Error> {
+        let mut connection = self.get_connection()?;
+
+        connection.transaction(|connection| {
+            check_promotion_names(connection, &promotion, Some(promotion_id))?;
+            Ok(diesel::update(promotion::table.find(promotion_id))
+                .set(&promotion)
+                .get_result::<Promotion>(connection)?)
+        })
+    }
+
+    fn merge_promotions(&self, promotion_id: i32, merged_id: i32) -> Result<Promotion, Error> {
+        if promotion_id == merged_id {
+            return Err(Error::InvalidData {
+                fields: vec![FieldError::new(
+                    "merged_id",
+                    "same_promotion",
+                    "A promotion can't be merged into itself.",
+                )],
+            });
+        }
+
+        let mut connection = self.get_connection()?;
+
+        connection.transaction(|connection| {
+            let promotion = promotion::table
+                .find(promotion_id)
+                .for_update()
+                .first::<Promotion>(connection)?;
+            promotion::table
+                .find(merged_id)
+                .select(promotion::id)
+                .for_update()
+                .first::<i32>(connection)
+                .optional()?
+                .ok_or_else(|| Error::unknown_reference("merged_id"))?;
+
+            // an event with the same name in both promotions breaks UQ_Name_Promotion
+            diesel::update(event::table.filter(event::promotion_id.eq(merged_id)))
+                .set(event::promotion_id.eq(promotion_id))
+                .execute(connection)?;
+            diesel::update(championship::table.filter(championship::promotion_id.eq(merged_id)))
+                .set(championship::promotion_id.eq(promotion_id))
+                .execute(connection)?;
+            diesel::update(event_series::table.filter(event_series::promotion_id.eq(merged_id)))
+                .set(event_series::promotion_id.eq(promotion_id))
+                .execute(connection)?;
+            diesel::delete(promotion::table.find(merged_id)).execute(connection)?;
+
+            Ok(promotion)
+        })
+    }
+
+    fn get_series(&self, page: i64) -> Result<Page<EventSeries>, Error> {
+        let mut connection = self.get_connection()?;
+
+        match event_series::table
+            .order_by(event_series::name.asc())
+            .paginate(page)
+            .load_and_count_pages::<EventSeries>(&mut connection)
+        {
+            Ok(page) => Ok(page),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_series_by(&self, series_id: i32) -> Result<EventSeries, Error> {
+        let mut connection = self.get_connection()?;
+
+        match event_series::table
+            .find(series_id)
+            .first::<EventSeries>(&mut connection)
+        {
+            Ok(series) => Ok(series),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_events_for_series(&self, series_id: i32) -> Result<Vec<Event>, Error> {
+        let mut connection = self.get_connection()?;
+
+        match event::table
+            .inner_join(promotion::table)
+            .filter(event::series_id.eq(series_id))
+            .order_by(event::date.asc())
+            .select(EVENT_COLUMNS)
+            .load::<Event>(&mut connection)
+        {
+            Ok(events) => Ok(events),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn new_series(&self, series: NewEventSeries) -> Result<EventSeries, Error> {
+        let mut connection = self.get_connection()?;
+
+        match series
+            .insert_into(event_series::table)
+            .get_result::<EventSeries>(&mut connection)
+        {
+            Ok(series) => Ok(series),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn update_series(&self, series_id: i32, series: NewEventSeries) -> Result<EventSeries, Error> {
+        let mut connection = self.get_connection()?;
+
+        match diesel::update(event_series::table.find(series_id))
+            .set(&series)
+            .get_result::<EventSeries>(&mut connection)
+        {
+            Ok(series) => Ok(series),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_best_match_for_event(&self, event_id: i32) -> Result<Option<RatedMatch>, Error> {
+        let mut connection = self.get_connection()?;
+
+        // between matches with the same average, the one with the most ratings wins
+        match match_::table
+            .inner_join(rating::table)
+            .filter(match_::event_id.eq(event_id))
+            .filter(rating::hidden.eq(false))
+            .group_by(match_::id)
+            .order_by((
+                dsl::avg(rating::score).desc(),
+                dsl::count(rating::id).desc(),
+                match_::position.asc(),
+            ))
+            .select((
+                match_::all_columns,
+                dsl::avg(rating::score),
+                dsl::count(rating::id),
+            ))
+            .first::<RatedMatch>(&mut connection)
+            .optional()
+        {
+            Ok(best_match) => Ok(best_match),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_venues(&self, page: i64) -> Result<Page<Venue>, Error> {
+        let mut connection = self.get_connection()?;
+
+        match venue::table
+            .order_by((venue::name.asc(), venue::city.asc()))
+            .paginate(page)
+            .load_and_count_pages::<Venue>(&mut connection)
+        {
+            Ok(page) => Ok(page),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_best_venues(&self, page: i64) -> Result<Page<RatedVenue>, Error> {
+        let mut connection = self.get_connection()?;
+
+        match venue::table
+            .inner_join(event::table.inner_join(match_::table.inner_join(rating::table)))
+            .filter(rating::hidden.eq(false))
+            .group_by(venue::id)
+            .order_by((
+                dsl::avg(rating::score).desc(),
+                dsl::count(rating::id).desc(),
+                venue::id.asc(),
+            ))
+            .select((
+                venue::all_columns,
+                dsl::avg(rating::score),
+                dsl::count(rating::id),
+            ))
+            .paginate(page)
+            .load_and_count_pages::<RatedVenue>(&mut connection)
+        {
+            Ok(page) => Ok(page),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_venue_by(&self, venue_id: i32) -> Result<Venue, Error> {
+        let mut connection = self.get_connection()?;
+
+        match venue::table.find(venue_id).first::<Venue>(&mut connection) {
+            Ok(venue) => Ok(venue),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_events_for_venue(&self, venue_id: i32, page: i64) -> Result<Page<RatedEvent>, Error> {
+        let mut connection = self.get_connection()?;
+
+        // events without any published rating are listed too
+        match event::table
+            .inner_join(promotion::table)
+            .left_join(
+                match_::table.left_join(
+                    rating::table.on(rating::match_id
+                        .eq(match_::id)
+                        .and(rating::hidden.eq(false))),
+                ),
+            )
+            .filter(event::venue_id.eq(venue_id))
+            .group_by((event::id, promotion::id))
+            .order_by(event::date.desc())
+            .select((
+                EVENT_COLUMNS,
+                dsl::avg(rating::score.nullable()),
+                dsl::count(rating::id.nullable()),
+            ))
+            .paginate(page)
+            .per_page(ITEMS_PER_PAGE)
+            .load_and_count_pages::<RatedEvent>(&mut connection)
+        {
+            Ok(page) => Ok(page),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn new_venue(&self, venue: NewVenue) -> Result<Venue, Error> {
+        let mut connection = self.get_connection()?;
+
+        match venue
+            .insert_into(venue::table)
+            .get_result::<Venue>(&mut connection)
+        {
+            Ok(venue) => Ok(venue),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn update_venue(&self, venue_id: i32, venue: NewVenue) -> Result<Venue, Error> {
+        let mut connection = self.get_connection()?;
+
+        match diesel::update(venue::table.find(venue_id))
+            .set(&venue)
+            .get_result::<Venue>(&mut connection)
+        {
+            Ok(venue) => Ok(venue),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_languages(&self) -> Result<Vec<LanguageInfo>, Error> {
+        let mut connection = self.get_connection()?;
+
+        match language::table
+            .order_by(language::code.asc())
+            .load::<LanguageInfo>(&mut connection)
+        {
+            Ok(languages) => Ok(languages),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_language_by(&self, code: Language) -> Result<LanguageInfo, Error> {
+        let mut connection = self.get_connection()?;
+
+        match language::table
+            .find(code)
+            .first::<LanguageInfo>(&mut connection)
+        {
+            Ok(language) => Ok(language),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn new_language(&self, language: LanguageInfo) -> Result<LanguageInfo, Error> {
+        let mut connection = self.get_connection()?;
+
+        match language
+            .insert_into(language::table)
+            .get_result::<LanguageInfo>(&mut connection)
+        {
+            Ok(language) => Ok(language),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_match_types(&self, language: Language) -> Result<Vec<LocalizedMatchType>, Error> {
+        let mut connection = self.get_connection()?;
+
+        match match_type::table
+            .left_join(
+                match_type_label::table.on(match_type_label::match_type_id
+                    .eq(match_type::id)
+                    .and(match_type_label::language_code.eq(language))),
+            )
+            .order_by(match_type::code.asc())
+            .select((match_type::all_columns, match_type_label::label.nullable()))
+            .load::<LocalizedMatchType>(&mut connection)
+        {
+            Ok(match_types) => Ok(match_types),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_types_for_match(
+        &self,
+        match_id: i32,
+        language: Language,
+    ) -> Result<Vec<LocalizedMatchType>, Error> {
+        let mut connection = self.get_connection()?;
+
+        match match_type::table
+            .inner_join(match_classification::table)
+            .left_join(
+                match_type_label::table.on(match_type_label::match_type_id
+                    .eq(match_type::id)
+                    .and(match_type_label::language_code.eq(language))),
+            )
+            .filter(match_classification::match_id.eq(match_id))
+            .order_by(match_type::code.asc())
+            .select((match_type::all_columns, match_type_label::label.nullable()))
+            .load::<LocalizedMatchType>(&mut connection)
+        {
+            Ok(match_types) => Ok(match_types),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn new_match_type(
+        &self,
+        match_type: NewMatchType,
+        labels: Vec<LocalizedLabel>,
+    ) -> Result<MatchType, Error> {
+        let mut connection = self.get_connection()?;
+
+        connection.transaction(|connection| {
+            let match_type = match_type
+                .insert_into(match_type::table)
+                .get_result::<MatchType>(connection)?;
+
+            let labels: Vec<NewMatchTypeLabel> = labels
+                .into_iter()
+                .map(|label| NewMatchTypeLabel {
+                    match_type_id: match_type.id(),
+                    language_code: label.language_code,
+                    label: label.label,
+                })
+                .collect();
+
+            labels
+                .insert_into(match_type_label::table)
+                .execute(connection)?;
+
+            Ok(match_type)
+        })
+    }
+
+    fn set_match_types(&self, match_id: i32, match_type_ids: Vec<i32>) -> Result<(), Error> {
+        let mut connection = self.get_connection()?;
+
+        connection.transaction(|connection| {
+            // deleting the types of a missing match would succeed silently
+            match_::table
+                .find(match_id)
+                .select(match_::id)
+                .first::<i32>(connection)?;
+
+            diesel::delete(
+                match_classification::table.filter(match_classification::match_id.eq(match_id)),
+            )
+            .execute(connection)?;
+
+            let classification: Vec<NewMatchClassification> = match_type_ids
+                .into_iter()
+                .map(|match_type_id| NewMatchClassification {
+                    match_id,
+                    match_type_id,
+                })
+                .collect();
+
+            classification
+                .insert_into(match_classification::table)
+                .on_conflict_do_nothing()
+                .execute(connection)?;
+
+            Ok(())
+        })
+    }
+
+    fn get_unclassified_descriptions(&self, language: Language) -> Result<Vec<MatchDesc>, Error> {
+        let mut connection = self.get_connection()?;
+
+        match match_desc::table
+            .filter(match_desc::language_code.eq(language))
+            .filter(dsl::not(match_desc::match_id.eq_any(
+                match_classification::table.select(match_classification::match_id),
+            )))
+            .order_by(match_desc::match_id.asc())
+            .load::<MatchDesc>(&mut connection)
+        {
+            Ok(descriptions) => Ok(descriptions),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_average_rating_for_match_type(
+        &self,
+        match_type_id: i32,
+    ) -> Result<Option<BigDecimal>, Error> {
+        let mut connection = self.get_connection()?;
+
+        let ratings = match_classification::table
+            .filter(match_classification::match_type_id.eq(match_type_id))
+            .inner_join(match_::table.inner_join(rating::table))
+            .filter(rating::hidden.eq(false))
+            .select(dsl::avg(rating::score));
+
+        match ratings.get_result(&mut connection) {
+            Ok(avg_score) => Ok(avg_score),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_championships(&self, page: i64) -> Result<Page<Championship>, Error> {
+        let mut connection = self.get_connection()?;
+
+        match championship::table
+            .order_by(championship::name.asc())
+            .paginate(page)
+            .load_and_count_pages::<Championship>(&mut connection)
+        {
+            Ok(page) => Ok(page),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_championship_by(&self, championship_id: i32) -> Result<Championship, Error> {
+        let mut connection = self.get_connection()?;
+
+        match championship::table
+            .find(championship_id)
+            .first::<Championship>(&mut connection)
+        {
+            Ok(championship) => Ok(championship),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_title_history(&self, championship_id: i32) -> Result<TitleHistory, Error> {
+        let mut connection = self.get_connection()?;
+
+        let championship = championship::table
+            .find(championship_id)
+            .first::<Championship>(&mut connection)?;
+        let average_score = title_match::table
+            .inner_join(match_::table.inner_join(rating::table))
+            .filter(title_match::championship_id.eq(championship_id))
+            .filter(title_match::title_changed.eq(false))
+            .filter(rating::hidden.eq(false))
+            .select(dsl::avg(rating::score))
+            .get_result::<Option<BigDecimal>>(&mut connection)?;
+
+        let reigns = title_reign::table
+            .inner_join(worker::table)
+            .filter(title_reign::championship_id.eq(championship_id))
+            .order_by(title_reign::start_date.asc())
+            .select((title_reign::all_columns, worker::name))
+            .load::<(TitleReign, String)>(&mut connection)?
+            .into_iter()
+            .map(|(reign, worker_name)| {
+                let (defense_count, average_score) =
+                    get_defense_stats(&mut connection, &reign, championship_id)?;
+                Ok(ReignDetails {
+                    reign,
+                    worker_name,
+                    defense_count,
+                    average_score,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(TitleHistory {
+            championship,
+            average_score,
+            reigns,
+        })
+    }
+
+    fn new_championship(&self, championship: NewChampionship) -> Result<Championship, Error> {
+        let mut connection = self.get_connection()?;
+
+        match championship
+            .insert_into(championship::table)
+            .get_result::<Championship>(&mut connection)
+        {
+            Ok(championship) => Ok(championship),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn update_championship(
+        &self,
+        championship_id: i32,
+        championship: NewChampionship,
+    ) -> Result<Championship, Error> {
+        let mut connection = self.get_connection()?;
+
+        match diesel::update(championship::table.find(championship_id))
+            .set(&championship)
+            .get_result::<Championship>(&mut connection)
+        {
+            Ok(championship) => Ok(championship),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn new_title_reign(&self, reign: NewTitleReign) -> Result<TitleReign, Error> {
+        let mut connection = self.get_connection()?;
+
+        match reign
+            .insert_into(title_reign::table)
+            .get_result::<TitleReign>(&mut connection)
+        {
+            Ok(reign) => Ok(reign),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn delete_title_reign(&self, championship_id: i32, reign_id: i32) -> Result<(), Error> {
+        let mut connection = self.get_connection()?;
+
+        match diesel::delete(
+            title_reign::table
+                .filter(title_reign::id.eq(reign_id))
+                .filter(title_reign::championship_id.eq(championship_id)),
+        )
+        .execute(&mut connection)?
+        {
+            0 => Err(Error::ResourceDoesNotExists),
+            _ => Ok(()),
+        }
+    }
+
+    fn get_contested_titles(&self, match_id: i32) -> Result<Vec<ContestedTitle>, Error> {
+        let mut connection = self.get_connection()?;
+
+        match title_match::table
+            .inner_join(championship::table)
+            .filter(title_match::match_id.eq(match_id))
+            .order_by(championship::name.asc())
+            .select((
+                championship::all_columns,
+                title_match::title_changed.nullable(),
+            ))
+            .load::<ContestedTitle>(&mut connection)
+        {
+            Ok(titles) => Ok(titles),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn set_title_match(&self, title_match: NewTitleMatch) -> Result<(), Error> {
+        let mut connection = self.get_connection()?;
+
+        match diesel::insert_into(title_match::table)
+            .values(&title_match)
+            .on_conflict((title_match::match_id, title_match::championship_id))
+            .do_update()
+            .set(title_match::title_changed.eq(title_match.title_changed))
+            .execute(&mut connection)
+        {
+            Ok(_) => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn delete_title_match(&self, match_id: i32, championship_id: i32) -> Result<(), Error> {
+        let mut connection = self.get_connection()?;
+
+        match diesel::delete(
+            title_match::table
+                .filter(title_match::match_id.eq(match_id))
+                .filter(title_match::championship_id.eq(championship_id)),
+        )
+        .execute(&mut connection)?
+        {
+            0 => Err(Error::ResourceDoesNotExists),
+            _ => Ok(()),
+        }
+    }
+
+    fn get_average_rating_for_event(&self, event_id: i32) -> Result<Option<BigDecimal>, Error> {
+        let mut connection = self.get_connection()?;
+
+        let ratings = match_::table
+            .filter(match_::event_id.eq(event_id))
+            .inner_join(rating::table)
+            .filter(rating::hidden.eq(false))
+            .select(dsl::avg(rating::score));
+
+        match ratings.get_result(&mut connection) {
+            Ok(avg_score) => Ok(avg_score),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_average_rating_for_match(&self, match_id: i32) -> Result<Option<BigDecimal>, Error> {
+        let mut connection = self.get_connection()?;
+
+        let ratings = rating::table
+            .filter(rating::match_id.eq(match_id))
+            .filter(rating::hidden.eq(false))
+            .select(dsl::avg(rating::score));
+
+        match ratings.first(&mut connection) {
+            Ok(avg_score) => Ok(avg_score),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn new_event(&self, event: NewEvent) -> Result<Event, Error> {
+        let mut connection = self.get_connection()?;
+
+        connection.transaction(|connection| {
+            let event_id = diesel::insert_into(event::table)
+                .values(&event)
+                .returning(event::id)
+                .get_result::<i32>(connection)?;
+            Ok(load_event(connection, event_id)?)
+        })
+    }
+
+    fn update_event(&self, event_id: i32, event: NewEvent) -> Result<Event, Error> {
+        let mut connection = self.get_connection()?;
+
+        connection.transaction(|connection| {
+            diesel::update(event::table.find(event_id))
+                .set(&event)
+                .returning(event::id)
+                .get_result::<i32>(connection)?;
+            Ok(load_event(connection, event_id)?)
+        })
+    }
+
+    fn patch_event(&self, event_id: i32, changes: EventChanges) -> Result<Event, Error> {
+        // diesel refuses empty changesets
+        if changes.is_empty() {
+            return self.get_event_by(event_id);
+        }
+
+        let mut connection = self.get_connection()?;
+
+        connection.transaction(|connection| {
+            diesel::update(event::table.find(event_id))
+                .set(&changes)
+                .returning(event::id)
+                .get_result::<i32>(connection)?;
+            Ok(load_event(connection, event_id)?)
+        })
+    }
+
+    fn delete_event(&self, event_id: i32) -> Result<(), Error> {
+        let mut connection = self.get_connection()?;
+
+        match diesel::delete(event::table.find(event_id)).execute(&mut connection)? {
+            0 => Err(Error::ResourceDoesNotExists),
+            _ => Ok(()),
+        }
+    }
+
+    fn new_match(
+        &self,
+        match_obj: NewMatch,
+        descriptions: Vec<LocalizedDescription>,
+    ) -> Result<MatchDetails, Error> {
+        let mut connection = self.get_connection()?;
+
+        connection.transaction(|connection| {
+            let position = next_position(connection, match_obj.event_id)?;
+            let match_obj = diesel::insert_into(match_::table)
+                .values((&match_obj, match_::position.eq(position)))
+                .get_result::<Match>(connection)?;
+            set_participants(connection, match_obj.id(), match_obj.workers())?;
+
+            let descriptions: Vec<NewMatchDesc> = descriptions
+                .into_iter()
+                .map(|description| NewMatchDesc {
+                    match_id: match_obj.id(),
+                    description: description.description,
+                    language_code: description.language_code,
+                })
+                .collect();
+
+            descriptions
+                .insert_into(match_desc::table)
+                .execute(connection)?;
+
+            let mut details = with_participants(connection, vec![match_obj])?;
+            details.pop().ok_or(Error::ResourceDoesNotExists)
+        })
+    }
+
+    fn update_match(&self, match_id: i32, match_obj: NewMatch) -> Result<MatchDetails, Error> {
+        let mut connection = self.get_connection()?;
+
+        connection.transaction(|connection| {
+            // a match moved to another event goes at the end of its card
+            let position = position_on_new_card(connection, match_id, match_obj.event_id)?;
+            let match_obj = diesel::update(match_::table.find(match_id))
+                .set((
+                    &match_obj,
+                    position.map(|position| match_::position.eq(position)),
+                ))
+                .get_result::<Match>(connection)?;
+            set_participants(connection, match_id, match_obj.workers())?;
+
+            let mut details = with_participants(connection, vec![match_obj])?;
+            details.pop().ok_or(Error::ResourceDoesNotExists)
+        })
+    }
+
+    fn patch_match(&self, match_id: i32, changes: MatchChanges) -> Result<MatchDetails, Error> {
+        // diesel refuses empty changesets
+        if changes.is_empty() {
+            return self.get_match_details(match_id);
+        }
+
+        let mut connection = self.get_connection()?;
+
+        connection.transaction(|connection| {
+            // a match moved to another event goes at the end of its card
+            let position = match changes.event_id {
+                Some(event_id) => position_on_new_card(connection, match_id, event_id)?,
+                None => None,
+            };
+            let match_obj = diesel::update(match_::table.find(match_id))
+                .set((
+                    &changes,
+                    position.map(|position| match_::position.eq(position)),
+                ))
+                .get_result::<Match>(connection)?;
+            if changes.workers.is_some() {
+                set_participants(connection, match_id, match_obj.workers())?;
+            }
+
+            let mut details = with_participants(connection, vec![match_obj])?;
+            details.pop().ok_or(Error::ResourceDoesNotExists)
+        })
+    }
+
+    fn reorder_card(&self, event_id: i32, match_ids: Vec<i32>) -> Result<Vec<MatchDetails>, Error> {
+        let mut connection = self.get_connection()?;
+
+        connection.transaction(|connection| {
+            // no match is added to the card while it's reordered
+            event::table
+                .find(event_id)
+                .select(event::id)
+                .for_update()
+                .first::<i32>(connection)?;
+
+            let mut card_ids = match_::table
+                .filter(match_::event_id.eq(event_id))
+                .select(match_::id)
+                .load::<i32>(connection)?;
+            let mut new_ids = match_ids.clone();
+            card_ids.sort_unstable();
+            new_ids.sort_unstable();
+            if card_ids != new_ids {
+                return Err(Error::InvalidData {
+                    fields: vec![FieldError::new(
+                        "match_ids",
+                        "wrong_matches",
+                        "Every match of the card must be given once.",
+                    )],
+                });
+            }
+
+            // two matches swapping places share a position until the end of the transaction
+            diesel::sql_query("SET CONSTRAINTS UQ_Match_Event_Position DEFERRED")
+                .execute(connection)?;
+            for (position, match_id) in (1..).zip(match_ids) {
+                diesel::update(match_::table.find(match_id))
+                    .set(match_::position.eq(position))
+                    .execute(connection)?;
+            }
+
+            let card = match_::table
+                .filter(match_::event_id.eq(event_id))
+                .order_by(match_::position.asc())
+                .load::<Match>(connection)?;
+            with_participants(connection, card)
+        })
+    }
+
+    fn set_match_result(&self, match_id: i32, result: MatchResult) -> Result<MatchDetails, Error> {
+        let mut connection = self.get_connection()?;
+
+        let match_obj = diesel::update(match_::table.find(match_id))
+            .set(&result)
+            .get_result::<Match>(&mut connection)?;
+
+        let mut details = with_participants(&mut connection, vec![match_obj])?;
+        details.pop().ok_or(Error::ResourceDoesNotExists)
+    }
+
+    fn new_worker_alias(&self, alias: NewWorkerAlias) -> Result<WorkerAlias, Error> {
+        let mut connection = self.get_connection()?;
+
+        connection.transaction(|connection| {
+            // a missing worker is a missing resource rather than a wrong reference
+            worker::table
+                .find(alias.worker_id)
+                .select(worker::id)
+                .first::<i32>(connection)?;
+
+            let duplicate = worker::table
+                .filter(lower(worker::name).eq(alias.name.to_lowercase()))
+                .filter(worker::id.ne(alias.worker_id))
+                .select(worker::id)
+                .first::<i32>(connection)
+                .optional()?;
+
+            if let Some(duplicate_id) = duplicate {
+                // the matches of the duplicate go to the worker, unless both were in the match
+                let both = match_participant::table
+                    .filter(match_participant::worker_id.eq(alias.worker_id))
+                    .select(match_participant::match_id)
+                    .load::<i32>(connection)?;
+                diesel::update(
+                    match_participant::table
+                        .filter(match_participant::worker_id.eq(duplicate_id))
+                        .filter(match_participant::match_id.ne_all(&both)),
+                )
+                .set(match_participant::worker_id.eq(alias.worker_id))
+                .execute(connection)?;
+                diesel::update(
+                    worker_alias::table.filter(worker_alias::worker_id.eq(duplicate_id)),
+                )
+                .set(worker_alias::worker_id.eq(alias.worker_id))
+                .execute(connection)?;
+                diesel::delete(worker::table.find(duplicate_id)).execute(connection)?;
+            }
+
+            Ok(alias
+                .insert_into(worker_alias::table)
+                .get_result::<WorkerAlias>(connection)?)
+        })
+    }
+
+    fn get_worker_aliases(&self, worker_id: i32) -> Result<Vec<WorkerAlias>, Error> {
+        let mut connection = self.get_connection()?;
+
+        match worker_alias::table
+            .filter(worker_alias::worker_id.eq(worker_id))
+            .order_by((
+                worker_alias::used_from.asc().nulls_last(),
+                worker_alias::id.asc(),
+            ))
+            .load::<WorkerAlias>(&mut connection)
+        {
+            Ok(aliases) => Ok(aliases),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn delete_worker_alias(&self, worker_id: i32, alias_id: i32) -> Result<(), Error> {
+        let mut connection = self.get_connection()?;
+
+        match diesel::delete(
+            worker_alias::table
+                .filter(worker_alias::id.eq(alias_id))
+                .filter(worker_alias::worker_id.eq(worker_id)),
+        )
+        .execute(&mut connection)?
+        {
+            0 => Err(Error::ResourceDoesNotExists),
+            _ => Ok(()),
+        }
+    }
+
+    fn delete_match(&self, match_id: i32) -> Result<(), Error> {
+        let mut connection = self.get_connection()?;
+
+        match diesel::delete(match_::table.find(match_id)).execute(&mut connection)? {
+            0 => Err(Error::ResourceDoesNotExists),
+            _ => Ok(()),
+        }
+    }
+
+    fn new_match_description(&self, description: NewMatchDesc) -> Result<MatchDesc, Error> {
+        let mut connection = self.get_connection()?;
+
+        match description
+            .insert_into(match_desc::table)
+            .get_result::<MatchDesc>(&mut connection)
+        {
+            Ok(matchdesc) => Ok(matchdesc),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn update_match_description(
+        &self,
+        match_id: i32,
+        language: Language,
+        description: String,
+    ) -> Result<MatchDesc, Error> {
+        let mut connection = self.get_connection()?;
+
+        match diesel::update(
+            match_desc::table
+                .filter(match_desc::match_id.eq(match_id))
+                .filter(match_desc::language_code.eq(language)),
+        )
+        .set(match_desc::description.eq(description))
+        .get_result::<MatchDesc>(&mut connection)
+        {
+            Ok(matchdesc) => Ok(matchdesc),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn delete_match_description(&self, match_id: i32, language: Language) -> Result<(), Error> {
+        let mut connection = self.get_connection()?;
+
+        match diesel::delete(
+            match_desc::table
+                .filter(match_desc::match_id.eq(match_id))
+                .filter(match_desc::language_code.eq(language)),
+        )
+        .execute(&mut connection)?
+        {
+            0 => Err(Error::ResourceDoesNotExists),
+            _ => Ok(()),
+        }
+    }
+
+    fn import_cards(&self, cards: Vec<ImportCard>, dry_run: bool) -> Result<ImportReport, Error> {
+        let mut connection = self.get_connection()?;
+
+        let events = if dry_run {
+            // the import really happens then gets rolled back, so the report is exactly what
+            // a real import would do
+            AnsiTransactionManager::begin_transaction(&mut *connection)?;
+            let events = import_all(&mut connection, cards);
+            AnsiTransactionManager::rollback_transaction(&mut *connection)?;
+
+            let mut events = events?;
+            for event in events.iter_mut().filter(|event| event.created) {
+                event.id = None;
+            }
+            events
+        } else {
+            connection.transaction(|connection| import_all(connection, cards))?
+        };
+
+        Ok(ImportReport { dry_run, events })
+    }
+
+    fn export_cards(
+        &self,
+        filter: &ExportFilter,
+        write: &mut dyn FnMut(ImportCard) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        let mut connection = self.get_connection()?;
+
+        let mut events = event::table
+            .inner_join(promotion::table)
+            .select(EVENT_COLUMNS)
+            .into_boxed();
+        if let Some(promotion) = &filter.promotion {
+            // the promotion may be given by its name or abbreviation
+            let promotion = promotion.trim().to_lowercase();
+            events = events.filter(
+                lower(promotion::name)
+                    .eq(promotion.clone())
+                    .nullable()
+                    .or(lower_nullable(promotion::abbreviation).eq(promotion)),
+            );
+        }
+        if let Some(from) = filter.from {
+            events = events.filter(event::date.ge(from));
+        }
+        if let Some(to) = filter.to {
+            events = events.filter(event::date.le(to));
+        }
+
+        let events = events
+            .order_by((event::date.asc(), event::id.asc()))
+            .load::<Event>(&mut connection)?;
+
+        for event in events {
+            let matches = match_::table
+                .filter(match_::event_id.eq(event.id()))
+                .order_by(match_::position.asc())
+                .load::<Match>(&mut connection)?;
+            let match_ids: Vec<i32> = matches.iter().map(|match_obj| match_obj.id()).collect();
+
+            let descriptions = match_desc::table
+                .filter(match_desc::match_id.eq_any(&match_ids))
+                .order_by(match_desc::id.asc())
+                .load::<MatchDesc>(&mut connection)?;
+            let ratings = rating::table
+                .left_join(users::table)
+                .filter(rating::match_id.eq_any(&match_ids))
+                // hidden ratings are not published
+                .filter(rating::hidden.eq(false))
+                .order_by((rating::publication_date.asc(), rating::id.asc()))
+                .select((rating::all_columns, users::username.nullable()))
+                .load::<(Rating, Option<String>)>(&mut connection)?;
+
+            let matches = matches
+                .iter()
+                .map(|match_obj| ImportMatch {
+                    workers: match_obj.workers().to_string(),
+                    descriptions: descriptions
+                        .iter()
+                        .filter(|description| description.match_id() == match_obj.id())
+                        .map(|description| LocalizedDescription {
+                            language_code: description.language_code(),
+                            description: description.description().to_string(),
+                        })
+                        .collect(),
+                    ratings: ratings
+                        .iter()
+                        .filter(|(rating, _)| rating.match_id() == match_obj.id())
+                        .map(|(rating, account)| ImportRating {
+                            language_code: rating.language_code(),
+                            username: rating.username().to_string(),
+                            score: rating.score().clone(),
+                            publication_date: *rating.publication_date(),
+                            opinion: rating.opinion().clone(),
+                            account: account.clone(),
+                        })
+                        .collect(),
+                })
+                .collect();
+
+            write(ImportCard {
+                event: CardEvent {
+                    name: event.name().to_string(),
+                    promotion: event.promotion().to_string(),
+                    date: *event.date(),
+                },
+                matches,
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn new_user(&self, user: NewUser) -> Result<User, Error> {
+        let mut connection = self.get_connection()?;
+
+        match user
+            .insert_into(users::table)
+            .get_result::<User>(&mut connection)
+        {
+            Ok(user) => Ok(user),
+            // UQ_Users_Username
+            Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+                Err(Error::UsernameTaken)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_user_by_username(&self, username: &str) -> Result<User, Error> {
+        let mut connection = self.get_connection()?;
+
+        match users::table
+            .filter(lower(users::username).eq(username.to_lowercase()))
+            .first::<User>(&mut connection)
+        {
+            Ok(user) => Ok(user),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn new_session(&self, session: NewSession) -> Result<(), Error> {
+        let mut connection = self.get_connection()?;
+
+        session
+            .insert_into(session::table)
+            .execute(&mut connection)?;
+        Ok(())
+    }
+
+    fn get_user_by_session(&self, token_hash: &str) -> Result<User, Error> {
+        let mut connection = self.get_connection()?;
+
+        match session::table
+            .inner_join(users::table)
+            .filter(session::token_hash.eq(token_hash))
+            .filter(session::expiration_date.gt(Utc::now().naive_utc()))
+            .select(users::all_columns)
+            .first::<User>(&mut connection)
+        {
+            Ok(user) => Ok(user),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn delete_session(&self, token_hash: &str) -> Result<(), Error> {
+        let mut connection = self.get_connection()?;
+
+        diesel::delete(session::table.filter(session::token_hash.eq(token_hash)))
+            .execute(&mut connection)?;
+        Ok(())
+    }
+
+    fn new_rating_claim(&self, claim: NewRatingClaim) -> Result<RatingClaim, Error> {
+        let mut connection = self.get_connection()?;
+
+        match claim
+            .insert_into(rating_claim::table)
+            .get_result::<RatingClaim>(&mut connection)
+        {
+            Ok(claim) => Ok(claim),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_pending_rating_claims(&self, page: i64) -> Result<Page<RatingClaim>, Error> {
+        let mut connection = self.get_connection()?;
+
+        match rating_claim::table
+            .filter(rating_claim::approved.is_null())
+            .order_by((rating_claim::creation_date.asc(), rating_claim::id.asc()))
+            .paginate(page)
+            .load_and_count_pages::<RatingClaim>(&mut connection)
+        {
+            Ok(page) => Ok(page),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn resolve_rating_claim(
+        &self,
+        claim_id: i32,
+        approved: bool,
+        moderator: &User,
+    ) -> Result<RatingClaim, Error> {
+        let mut connection = self.get_connection()?;
+
+        connection.transaction(|connection| {
+            // a claim is only decided once
+            let claim = rating_claim::table
+                .find(claim_id)
+                .filter(rating_claim::approved.is_null())
+                .for_update()
+                .first::<RatingClaim>(connection)?;
+
+            if approved {
+                // the matches the account already rated keep their anonymous rating, an account
+                // only rates a match once
+                let rated = rating::table
+                    .filter(rating::user_id.eq(claim.user_id()))
+                    .select(rating::match_id)
+                    .load::<i32>(connection)?;
+
+                diesel::update(
+                    rating::table
+                        .filter(rating::user_id.is_null())
+                        .filter(lower(rating::username).eq(claim.username().to_lowercase()))
+                        .filter(rating::match_id.ne_all(rated)),
+                )
+                .set(rating::user_id.eq(claim.user_id()))
+                .execute(connection)?;
+            }
+
+            Ok(diesel::update(rating_claim::table.find(claim_id))
+                .set((
+                    rating_claim::approved.eq(approved),
+                    rating_claim::moderator_id.eq(moderator.id()),
+                    rating_claim::resolution_date.eq(Utc::now().naive_utc()),
+                ))
+                .get_result::<RatingClaim>(connection)?)
+        })
+    }
+
+    fn get_users(&self, page: i64) -> Result<Page<User>, Error> {
+        let mut connection = self.get_connection()?;
+
+        match users::table
+            .order_by(users::id.asc())
+            .paginate(page)
+            .load_and_count_pages::<User>(&mut connection)
+        {
+            Ok(page) => Ok(page),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn get_user_by(&self, user_id: i32) -> Result<User, Error> {
+        let mut connection = self.get_connection()?;
+
+        match users::table.find(user_id).first::<User>(&mut connection) {
+            Ok(user) => Ok(user),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn set_user_role(&self, user_id: i32, role: Role) -> Result<User, Error> {
+        let mut connection = self.get_connection()?;
+
+        connection.transaction(|connection| {
+            let user = users::table.find(user_id).first::<User>(connection)?;
+
+            if user.role() == Role::Admin && role != Role::Admin {
+                // the admins are locked until the end of the transaction, so two of them
+                // can't demote each other at the same time
+                let admins = users::table
+                    .filter(users::role_code.eq(Role::Admin))
+                    .select(users::id)
+                    .for_update()
+                    .load::<i32>(connection)?;
+
+                if admins.len() <= 1 {
+                    return Err(Error::InvalidData {
+                        fields: vec![FieldError::new(
+                            "role",
+                            "last_admin",
+                            "The last administrator can't lose its role.",
+                        )],
+                    });
+                }
+            }
+
+            Ok(diesel::update(users::table.find(user_id))
+                .set(users::role_code.eq(role))
+                .get_result::<User>(connection)?)
+        })
+    }
+
+    fn has_admin(&self) -> Result<bool, Error> {
+        let mut connection = self.get_connection()?;
+
+        match dsl::select(dsl::exists(
+            users::table.filter(users::role_code.eq(Role::Admin)),
+        ))
+        .get_result::<bool>(&mut connection)
+        {
+            Ok(has_admin) => Ok(has_admin),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use chrono::NaiveDate;
+    use diesel::prelude::*;
+    use dotenvy::dotenv;
+    use std::env;
+
+    use super::{search_all, sort_ratings};
+    use crate::{
+        entities::{Language, NewEvent, NewPromotion, NewRating},
+        pagination::{Order, RatingSort},
+        schema::{event, match_, promotion, rating},
+    };
+
+    /// Connection whose changes are rolled back. The tests needing a database are skipped when
+    /// `DATABASE_URL` is not set.
+    fn test_connection() -> Option<PgConnection> {
+        dotenv().ok();
+        let url = env::var("DATABASE_URL").ok()?;
+        let mut connection =
+            PgConnection::establish(&url).expect("DATABASE_URL must lead to a migrated database");
+        connection.begin_test_transaction().unwrap();
+        Some(connection)
+    }
+
+    #[test]
+    fn search_goes_on_after_a_kind_of_items_ran_out() {
+        let Some(mut connection) = test_connection() else {
+            return;
+        };
+
+        let promotion_id = NewPromotion {
+            name: "Plugh Wrestling".to_string(),
+            abbreviation: None,
+            country: None,
+            active_from: None,
+            active_until: None,
+        }
+        .insert_into(promotion::table)
+        .returning(promotion::id)
+        .get_result::<i32>(&mut connection)
+        .unwrap();
+        // 11 events, one more than a page, and a single match
+        for i in 1..=11 {
+            let event_id = NewEvent {
+                name: format!("Xyzzy Show {}", i),
+                promotion_id,
+                date: NaiveDate::from_ymd_opt(2025, 1, i).unwrap(),
+                venue_id: None,
+                attendance: None,
+                series_id: None,
+            }
+            .insert_into(event::table)
+            .returning(event::id)
+            .get_result::<i32>(&mut connection)
+            .unwrap();
+            if i == 1 {
+                diesel::insert_into(match_::table)
+                    .values((
+                        match_::event_id.eq(event_id),
+                        match_::workers.eq("Xyzzy vs. Frobozz"),
+                        match_::position.eq(1),
+                    ))
+                    .execute(&mut connection)
+                    .unwrap();
+            }
+        }
+
+        let results = search_all(&mut connection, "xyzzy", Language::ENGLISH, 2).unwrap();
+        assert_eq!(results.events.items.len(), 1);
+        assert_eq!(results.events.total_items, 11);
+        assert!(results.events.has_prev);
+        // past the last page of the matches, which are still counted
+        assert!(results.matches.items.is_empty());
+        assert_eq!(results.matches.total_items, 1);
+        assert_eq!(results.matches.page_total, 1);
+        assert!(!results.matches.has_next);
+        assert_eq!(results.descriptions.total_items, 0);
+        assert_eq!(results.ratings.total_items, 0);
+    }
+
+    #[test]
+    fn ratings_tied_on_the_sort_keep_the_order_of_their_ids() {
+        let Some(mut connection) = test_connection() else {
+            return;
+        };
+
+        let promotion_id = NewPromotion {
+            name: "Plugh Wrestling".to_string(),
+            abbreviation: None,
+            country: None,
+            active_from: None,
+            active_until: None,
+        }
+        .insert_into(promotion::table)
+        .returning(promotion::id)
+        .get_result::<i32>(&mut connection)
+        .unwrap();
+        let event_id = NewEvent {
+            name: "Xyzzy Show".to_string(),
+            promotion_id,
+            date: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            venue_id: None,
+            attendance: None,
+            series_id: None,
+        }
+        .insert_into(event::table)
+        .returning(event::id)
+        .get_result::<i32>(&mut connection)
+        .unwrap();
+        let match_id = diesel::insert_into(match_::table)
+            .values((
+                match_::event_id.eq(event_id),
+                match_::workers.eq("Xyzzy vs. Frobozz"),
+                match_::position.eq(1),
+            ))
+            .returning(match_::id)
+            .get_result::<i32>(&mut connection)
+            .unwrap();
+
+        // the same score published at the same time by everyone
+        let publication_date = NaiveDate::from_ymd_opt(2025, 1, 2)
+            .unwrap()
+            .and_hms_opt(20, 0, 0)
+            .unwrap();
+        let ids = ["anna", "bert", "cleo"]
+            .into_iter()
+            .map(|username| {
+                NewRating {
+                    match_id,
+                    language_code: Language::ENGLISH,
+                    username: username.to_string(),
+                    score: BigDecimal::from(4),
+                    publication_date,
+                    opinion: None,
+                    user_id: None,
+                }
+                .insert_into(rating::table)
+                .returning(rating::id)
+                .get_result::<i32>(&mut connection)
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let reversed = ids.iter().rev().copied().collect::<Vec<_>>();
+
+        for (sort, order, expected) in [
+            (RatingSort::Date, Order::Asc, &ids),
+            (RatingSort::Date, Order::Desc, &reversed),
+            (RatingSort::Score, Order::Asc, &ids),
+            (RatingSort::Score, Order::Desc, &reversed),
+        ] {
+            let sorted = sort_ratings(
+                rating::table
+                    .filter(rating::match_id.eq(match_id))
+                    .select(rating::id)
+                    .into_boxed(),
+                sort,
+                order,
+            )
+            .load::<i32>(&mut connection)
+            .unwrap();
+            assert_eq!(&sorted, expected);
+        }
+    }
 }
@@ -1,12 +1,38 @@
 use crate::{
     AppState,
+    auth::{self, Admin, AuthenticatedUser, Authorized, Editor, Moderator, Rater, SessionToken},
     database::DatabaseOperations,
-    entities::{Event, Language, Match, MatchDesc, NewRating, Rating},
-    pagination::Page,
+    entities::{
+        Championship, ContestedTitle, Event, EventChanges, EventSeries, FlaggedRating, Language,
+        LanguageInfo, LocalizedDescription, LocalizedLabel, LocalizedMatchType, MatchChanges,
+        MatchDesc, MatchDetails, MatchHit, MatchResult, MatchType, ModerationAction, ModerationLog,
+        NegotiatedDescription, NewChampionship, NewEvent, NewEventSeries, NewMatch, NewMatchDesc,
+        NewMatchType, NewPromotion, NewRating, NewRatingClaim, NewRatingFlag, NewTitleMatch,
+        NewTitleReign, NewUser, NewVenue, NewWorkerAlias, Promotion, RatedEvent, RatedMatch,
+        RatedVenue, Rating, RatingChanges, RatingClaim, RatingFlag, RatingRevision, Role,
+        SearchResults, Segment, SeriesEdition, TitleHistory, TitleReign, User, Venue, WorkerAlias,
+        WorkerProfile,
+    },
+    errors::Error,
+    export::{self, ExportFilter, ExportFormat, ExportWriter},
+    import::{self, ImportFormat, ImportReport},
+    languages::{self, AcceptedLanguages},
+    pagination::{CursorPage, EventSort, Order, Page, RatingSort},
+    validation::{self, FieldError},
+    workers,
 };
 use bigdecimal::BigDecimal;
-use chrono::Utc;
-use rocket::{State, get, http::Status, post, response::status::NotFound, serde::json::Json};
+use chrono::{NaiveDate, Utc};
+use rocket::{
+    State,
+    data::{Data, ToByteUnit},
+    delete, get,
+    http::ContentType,
+    patch, post, put,
+    response::stream::TextStream,
+    serde::json::Json,
+    tokio::{sync::mpsc, task},
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize)]
@@ -32,221 +58,567 @@ pub struct ApiResponse {
 pub struct RequestRating {
     pub match_id: i32,
     pub language_code: Language,
-    pub username: String,
     pub score: BigDecimal,
     pub opinion: Option<String>,
 }
 
-/// We convert the request-made rating to a Database-compliant NewRating. We're doing the time of
-/// publication server-side so there isn't any cheater who put a date way before or way after the
-/// actual publication date.
-impl From<RequestRating> for NewRating {
-    fn from(value: RequestRating) -> Self {
+#[derive(Deserialize)]
+pub struct RequestFlag {
+    pub reason: String,
+}
+
+#[derive(Deserialize)]
+pub struct RequestClaim {
+    /// Username of the anonymous ratings
+    pub username: String,
+    /// Anything letting a moderator check that the account wrote the ratings
+    pub proof: String,
+}
+
+#[derive(Deserialize)]
+pub struct RequestClaimDecision {
+    pub approved: bool,
+}
+
+#[derive(Deserialize)]
+pub struct RequestPromotionMerge {
+    /// Promotion deleted once its events are moved
+    pub merged_id: i32,
+}
+
+#[derive(Deserialize)]
+pub struct RequestModeration {
+    pub action: ModerationAction,
+    /// Kept in the audit trail
+    pub reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RequestRole {
+    pub role: Role,
+}
+
+#[derive(Deserialize)]
+pub struct RequestAccount {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Deserialize)]
+pub struct RequestMatch {
+    pub event_id: i32,
+    pub workers: String,
+    #[serde(default)]
+    pub segment: Segment,
+    /// Descriptions created along with the match, in any number of languages
+    #[serde(default)]
+    pub descriptions: Vec<LocalizedDescription>,
+}
+
+#[derive(Deserialize)]
+pub struct RequestAlias {
+    pub name: String,
+    /// Only for ring names, when the worker started using it
+    pub used_from: Option<NaiveDate>,
+    pub used_until: Option<NaiveDate>,
+}
+
+#[derive(Deserialize)]
+pub struct RequestReign {
+    pub worker_id: i32,
+    pub start_date: NaiveDate,
+    /// Missing for the current champion
+    pub end_date: Option<NaiveDate>,
+    pub won_match_id: Option<i32>,
+}
+
+#[derive(Deserialize)]
+pub struct RequestTitleMatch {
+    #[serde(default)]
+    pub title_changed: bool,
+}
+
+#[derive(Deserialize)]
+pub struct RequestCardOrder {
+    /// Every match of the card, the first one first
+    pub match_ids: Vec<i32>,
+}
+
+#[derive(Deserialize)]
+pub struct RequestMatchType {
+    pub code: String,
+    pub labels: Vec<LocalizedLabel>,
+}
+
+#[derive(Deserialize)]
+pub struct RequestMatchTypes {
+    pub match_type_ids: Vec<i32>,
+}
+
+#[derive(Deserialize)]
+pub struct RequestDescription {
+    pub description: String,
+}
+
+impl RequestRating {
+    /// We convert the request-made rating to a Database-compliant NewRating. We're doing the time
+    /// of publication server-side so there isn't any cheater who put a date way before or way
+    /// after the actual publication date. The author is the authenticated user, never something
+    /// the client could choose.
+    pub fn into_new_rating(self, author: &User) -> NewRating {
         NewRating {
-            match_id: value.match_id,
-            language_code: value.language_code,
-            username: value.username,
-            score: value.score,
-            opinion: value.opinion,
+            match_id: self.match_id,
+            language_code: self.language_code,
+            username: author.username().to_string(),
+            score: self.score,
+            opinion: self.opinion,
             publication_date: Utc::now().naive_utc(),
+            user_id: Some(author.id()),
         }
     }
 }
 
-#[get("/events?<page>")]
+/// The most recent events come first unless the client asks for another sorting
+#[get("/events?<page>&<per_page>&<sort>&<order>")]
 pub fn get_events(
     state: &State<AppState>,
     page: i64,
-) -> Result<Json<ApiResponseWithData<Page<Event>>>, NotFound<String>> {
-    match state.database.get_events(page) {
-        Ok(data) => Ok(Json(ApiResponseWithData {
-            message: "Operation done".to_string(),
-            data,
-        })),
-        Err(_) => Err(NotFound("No ressources".to_string())),
-    }
+    per_page: Option<i64>,
+    sort: Option<EventSort>,
+    order: Option<Order>,
+) -> Result<Json<ApiResponseWithData<Page<Event>>>, Error> {
+    validation::validate_per_page(per_page)?;
+    let data = state.database.get_events(
+        page,
+        per_page,
+        sort.unwrap_or(EventSort::Date),
+        order.unwrap_or(Order::Desc),
+    )?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
 }
 
 #[get("/events/<event_id>")]
 pub fn get_event(
     state: &State<AppState>,
     event_id: i32,
-) -> Result<Json<ApiResponseWithData<Event>>, (Status, Json<ApiResponse>)> {
-    match state.database.get_event_by(event_id) {
-        Ok(data) => Ok(Json(ApiResponseWithData {
-            message: "Operation done".to_string(),
-            data,
-        })),
-        Err(_) => Err((
-            Status::UnprocessableEntity,
-            Json(ApiResponse {
-                message: "Could not get the data.".to_string(),
-            }),
-        )),
-    }
+) -> Result<Json<ApiResponseWithData<Event>>, Error> {
+    let data = state.database.get_event_by(event_id)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
 }
 
-#[get("/events/<event_id>/matches")]
+/// The results are only given to the clients asking for spoilers
+#[get("/events/<event_id>/matches?<spoilers>&<match_type>")]
 pub fn get_event_matches(
     state: &State<AppState>,
     event_id: i32,
-) -> Result<Json<ApiResponseWithData<Vec<Match>>>, (Status, Json<ApiResponse>)> {
-    match state.database.get_card(event_id) {
-        Ok(data) => Ok(Json(ApiResponseWithData {
-            message: "Operation done".to_string(),
-            data,
-        })),
-        Err(_) => Err((
-            Status::UnprocessableEntity,
-            Json(ApiResponse {
-                message: "Could not get the data.".to_string(),
-            }),
-        )),
+    spoilers: Option<bool>,
+    match_type: Option<&str>,
+) -> Result<Json<ApiResponseWithData<Vec<MatchDetails>>>, Error> {
+    let mut data = state.database.get_card(event_id, match_type)?;
+    if !spoilers.unwrap_or(false) {
+        data.iter_mut().for_each(MatchDetails::hide_result);
     }
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
 }
 
-#[get("/match/<match_id>")]
+/// The result is only given to the clients asking for spoilers
+#[get("/match/<match_id>?<spoilers>")]
 pub fn get_match(
     state: &State<AppState>,
     match_id: i32,
-) -> Result<Json<ApiResponseWithData<Match>>, (Status, Json<ApiResponse>)> {
-    match state.database.get_match_by(match_id) {
-        Ok(data) => Ok(Json(ApiResponseWithData {
-            message: "Operation done".to_string(),
-            data,
-        })),
-        Err(_) => Err((
-            Status::UnprocessableEntity,
-            Json(ApiResponse {
-                message: "Could not get the data.".to_string(),
-            }),
-        )),
+    spoilers: Option<bool>,
+) -> Result<Json<ApiResponseWithData<MatchDetails>>, Error> {
+    let mut data = state.database.get_match_details(match_id)?;
+    if !spoilers.unwrap_or(false) {
+        data.hide_result();
     }
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
 }
 
+/// Without `lang`, the language is negotiated from the `Accept-Language` header. When the match
+/// has no description in the asked language, the fallback chain is followed.
 #[get("/match/<match_id>/description?<lang>")]
 pub fn get_match_desc(
     state: &State<AppState>,
     match_id: i32,
-    lang: Language,
-) -> Result<Json<ApiResponseWithData<MatchDesc>>, (Status, Json<ApiResponse>)> {
-    match state.database.get_match_description(match_id, lang) {
-        Ok(data) => Ok(Json(ApiResponseWithData {
-            message: "Operation done".to_string(),
-            data,
-        })),
-        Err(_) => Err((
-            Status::UnprocessableEntity,
-            Json(ApiResponse {
-                message: "Could not get the data.".to_string(),
-            }),
-        )),
-    }
+    lang: Option<Language>,
+    accepted: AcceptedLanguages,
+) -> Result<Json<ApiResponseWithData<NegotiatedDescription>>, Error> {
+    let preferences =
+        languages::preferences(lang, &accepted, &state.language_fallback, &state.database)?;
+    let mut descriptions = state.database.get_match_descriptions(match_id)?;
+
+    let Some(position) = preferences.languages.iter().find_map(|language| {
+        descriptions
+            .iter()
+            .position(|description| description.language_code() == *language)
+    }) else {
+        return Err(Error::ResourceDoesNotExists);
+    };
+    let description = descriptions.swap_remove(position);
+    let data = NegotiatedDescription {
+        fallback: preferences.is_fallback(description.language_code()),
+        requested_language: preferences.requested,
+        requested_tag: preferences.first_tag,
+        description,
+    };
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
 }
 
-#[get("/events/<event_id>/ratings?<page>&<lang>")]
+/// The ratings lists show the most recent ratings first unless the client asks for another sorting
+#[get("/events/<event_id>/ratings?<page>&<lang>&<per_page>&<sort>&<order>")]
 pub fn get_event_ratings(
     state: &State<AppState>,
     event_id: i32,
     page: i64,
     lang: Language,
-) -> Result<Json<ApiResponseWithData<Page<Rating>>>, (Status, Json<ApiResponse>)> {
-    match state.database.get_ratings_for_event(event_id, page, lang) {
-        Ok(data) => Ok(Json(ApiResponseWithData {
-            message: "Operation done".to_string(),
-            data,
-        })),
-        Err(_) => Err((
-            Status::UnprocessableEntity,
-            Json(ApiResponse {
-                message: "Could not get the data.".to_string(),
-            }),
-        )),
-    }
+    per_page: Option<i64>,
+    sort: Option<RatingSort>,
+    order: Option<Order>,
+) -> Result<Json<ApiResponseWithData<Page<Rating>>>, Error> {
+    validation::validate_language(lang, "lang", &state.database)?;
+    validation::validate_per_page(per_page)?;
+    let data = state.database.get_ratings_for_event(
+        event_id,
+        page,
+        per_page,
+        lang,
+        sort.unwrap_or(RatingSort::Date),
+        order.unwrap_or(Order::Desc),
+    )?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
 }
 
-#[get("/match/<match_id>/ratings?<page>&<lang>")]
+#[get("/match/<match_id>/ratings?<page>&<lang>&<per_page>&<sort>&<order>")]
 pub fn get_match_ratings(
     state: &State<AppState>,
     match_id: i32,
     page: i64,
     lang: Language,
-) -> Result<Json<ApiResponseWithData<Page<Rating>>>, (Status, Json<ApiResponse>)> {
-    match state.database.get_ratings_for_match(match_id, page, lang) {
-        Ok(data) => Ok(Json(ApiResponseWithData {
-            message: "Operation done".to_string(),
-            data: data,
-        })),
-        Err(_) => Err((
-            Status::UnprocessableEntity,
-            Json(ApiResponse {
-                message: "Could not get the data.".to_string(),
-            }),
-        )),
-    }
+    per_page: Option<i64>,
+    sort: Option<RatingSort>,
+    order: Option<Order>,
+) -> Result<Json<ApiResponseWithData<Page<Rating>>>, Error> {
+    validation::validate_language(lang, "lang", &state.database)?;
+    validation::validate_per_page(per_page)?;
+    let data = state.database.get_ratings_for_match(
+        match_id,
+        page,
+        per_page,
+        lang,
+        sort.unwrap_or(RatingSort::Date),
+        order.unwrap_or(Order::Desc),
+    )?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
 }
 
-#[get("/ratings?<page>&<lang>")]
+#[get("/ratings?<page>&<lang>&<match_type>&<per_page>&<sort>&<order>")]
 pub fn get_ratings(
     state: &State<AppState>,
     page: i64,
     lang: Language,
-) -> Result<Json<ApiResponseWithData<Page<Rating>>>, (Status, Json<ApiResponse>)> {
-    match state.database.get_ratings(page, lang) {
-        Ok(data) => Ok(Json(ApiResponseWithData {
-            message: "Operation done".to_string(),
-            data,
-        })),
-        Err(_) => Err((
-            Status::UnprocessableEntity,
-            Json(ApiResponse {
-                message: "Could not get the data.".to_string(),
-            }),
-        )),
+    match_type: Option<&str>,
+    per_page: Option<i64>,
+    sort: Option<RatingSort>,
+    order: Option<Order>,
+) -> Result<Json<ApiResponseWithData<Page<Rating>>>, Error> {
+    validation::validate_language(lang, "lang", &state.database)?;
+    validation::validate_per_page(per_page)?;
+    let data = state.database.get_ratings(
+        page,
+        per_page,
+        lang,
+        match_type,
+        sort.unwrap_or(RatingSort::Date),
+        order.unwrap_or(Order::Desc),
+    )?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+/// Searches the events, matches, descriptions and ratings. Like everywhere else, the results of
+/// the matches are only given to the clients asking for spoilers.
+#[get("/search?<q>&<lang>&<page>&<spoilers>")]
+pub fn search(
+    state: &State<AppState>,
+    q: &str,
+    lang: Language,
+    page: i64,
+    spoilers: Option<bool>,
+) -> Result<Json<ApiResponseWithData<SearchResults>>, Error> {
+    validation::validate_language(lang, "lang", &state.database)?;
+    validation::validate_search(q)?;
+    let mut data = state.database.search(q.trim(), lang, page)?;
+    if !spoilers.unwrap_or(false) {
+        data.matches
+            .items
+            .iter_mut()
+            .for_each(MatchHit::hide_result);
+        data.descriptions
+            .items
+            .iter_mut()
+            .for_each(MatchHit::hide_result);
     }
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+/// Feed of the global ratings, from the most recent. New ratings don't shift the next pages like
+/// they do with the page numbers, which are used instead when given.
+#[get("/ratings?<lang>&<cursor>&<per_page>&<match_type>&<count>", rank = 2)]
+pub fn get_ratings_feed(
+    state: &State<AppState>,
+    lang: Language,
+    cursor: Option<&str>,
+    per_page: Option<i64>,
+    match_type: Option<&str>,
+    count: Option<bool>,
+) -> Result<Json<ApiResponseWithData<CursorPage<Rating>>>, Error> {
+    validation::validate_language(lang, "lang", &state.database)?;
+    validation::validate_per_page(per_page)?;
+    let cursor = cursor.map(validation::parse_cursor).transpose()?;
+    let data = state.database.get_ratings_feed(
+        cursor,
+        per_page,
+        lang,
+        match_type,
+        count.unwrap_or(false),
+    )?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
 }
 
 #[post("/match/ratings", format = "application/json", data = "<rating>")]
 pub fn add_match_rating(
+    author: Authorized<Rater>,
     state: &State<AppState>,
     rating: Json<RequestRating>,
-) -> Result<Json<ApiResponse>, (Status, Json<ApiResponse>)> {
-    match state.database.new_rating(rating.0.into()) {
-        Ok(()) => Ok(Json(ApiResponse {
-            message: "Rating added".to_string(),
-        })),
-        Err(_) => Err((
-            Status::UnprocessableEntity,
-            Json(ApiResponse {
-            message: "Could not add this rating. You may have already submitted a rating with this username.".to_string(),
-        }))),
+) -> Result<Json<ApiResponse>, Error> {
+    rating.validate(&state.rating_rules, &state.database)?;
+    state
+        .database
+        .new_rating(rating.0.into_new_rating(&author.user))?;
+    Ok(Json(ApiResponse {
+        message: "Rating added".to_string(),
+    }))
+}
+
+/// A hidden rating is only shown to its author and to the moderators, the others are told it
+/// doesn't exist
+#[get("/ratings/<rating_id>")]
+pub fn get_rating(
+    viewer: Option<AuthenticatedUser>,
+    state: &State<AppState>,
+    rating_id: i32,
+) -> Result<Json<ApiResponseWithData<Rating>>, Error> {
+    let data = state.database.get_rating_by(rating_id)?;
+    if data.hidden() {
+        match viewer {
+            Some(viewer) if auth::check_rating_author(&viewer.user, &data).is_ok() => (),
+            _ => return Err(Error::ResourceDoesNotExists),
+        }
+    }
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+/// Only the author of the rating or a moderator can change it, the previous version is kept
+#[patch(
+    "/ratings/<rating_id>",
+    format = "application/json",
+    data = "<changes>"
+)]
+pub fn patch_rating(
+    editor: Authorized<Rater>,
+    state: &State<AppState>,
+    rating_id: i32,
+    changes: Json<RatingChanges>,
+) -> Result<Json<ApiResponseWithData<Rating>>, Error> {
+    let rating = state.database.get_rating_by(rating_id)?;
+    auth::check_rating_author(&editor.user, &rating)?;
+    changes.validate(&state.rating_rules)?;
+
+    let data = state
+        .database
+        .update_rating(rating_id, changes.0, &editor.user)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Rating updated".to_string(),
+        data,
+    }))
+}
+
+#[delete("/ratings/<rating_id>")]
+pub fn delete_rating(
+    editor: Authorized<Rater>,
+    state: &State<AppState>,
+    rating_id: i32,
+) -> Result<Json<ApiResponse>, Error> {
+    let rating = state.database.get_rating_by(rating_id)?;
+    auth::check_rating_author(&editor.user, &rating)?;
+
+    // a moderator deleting someone else's rating leaves a trace in the audit trail
+    if rating.user_id() == Some(editor.user.id()) {
+        state.database.delete_rating(rating_id)?;
+    } else {
+        state
+            .database
+            .moderate_rating(rating_id, ModerationAction::Delete, &editor.user, None)?;
+    }
+    Ok(Json(ApiResponse {
+        message: "Rating deleted".to_string(),
+    }))
+}
+
+/// The previous versions may contain what the author wanted to remove, so only the author and
+/// the moderators can see them
+#[get("/ratings/<rating_id>/revisions")]
+pub fn get_rating_revisions(
+    authenticated: AuthenticatedUser,
+    state: &State<AppState>,
+    rating_id: i32,
+) -> Result<Json<ApiResponseWithData<Vec<RatingRevision>>>, Error> {
+    let rating = state.database.get_rating_by(rating_id)?;
+    auth::check_rating_author(&authenticated.user, &rating)?;
+
+    let data = state.database.get_rating_revisions(rating_id)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+/// Any account can flag a rating once, even the ones not allowed to rate
+#[post(
+    "/ratings/<rating_id>/flags",
+    format = "application/json",
+    data = "<flag>"
+)]
+pub fn flag_rating(
+    authenticated: AuthenticatedUser,
+    state: &State<AppState>,
+    rating_id: i32,
+    flag: Json<RequestFlag>,
+) -> Result<Json<ApiResponseWithData<RatingFlag>>, Error> {
+    flag.validate()?;
+    let rating = state.database.get_rating_by(rating_id)?;
+    // a hidden rating can't be flagged by those who can't see it
+    if rating.hidden() && auth::check_rating_author(&authenticated.user, &rating).is_err() {
+        return Err(Error::ResourceDoesNotExists);
     }
+
+    let data = state.database.new_rating_flag(NewRatingFlag {
+        rating_id,
+        user_id: authenticated.user.id(),
+        reason: flag.0.reason.trim().to_string(),
+        creation_date: Utc::now().naive_utc(),
+    })?;
+    Ok(Json(ApiResponseWithData {
+        message: "Rating flagged".to_string(),
+        data,
+    }))
+}
+
+#[get("/ratings/<rating_id>/flags")]
+pub fn get_rating_flags(
+    _moderator: Authorized<Moderator>,
+    state: &State<AppState>,
+    rating_id: i32,
+) -> Result<Json<ApiResponseWithData<Vec<RatingFlag>>>, Error> {
+    let data = state.database.get_rating_flags(rating_id)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+#[get("/moderation/queue?<page>")]
+pub fn get_moderation_queue(
+    _moderator: Authorized<Moderator>,
+    state: &State<AppState>,
+    page: i64,
+) -> Result<Json<ApiResponseWithData<Page<FlaggedRating>>>, Error> {
+    let data = state.database.get_flagged_ratings(page)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+/// Restoring a rating that isn't hidden dismisses its flags
+#[post(
+    "/ratings/<rating_id>/moderation",
+    format = "application/json",
+    data = "<moderation>"
+)]
+pub fn moderate_rating(
+    moderator: Authorized<Moderator>,
+    state: &State<AppState>,
+    rating_id: i32,
+    moderation: Json<RequestModeration>,
+) -> Result<Json<ApiResponseWithData<ModerationLog>>, Error> {
+    let RequestModeration { action, reason } = moderation.0;
+    let data = state
+        .database
+        .moderate_rating(rating_id, action, &moderator.user, reason)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Rating moderated".to_string(),
+        data,
+    }))
+}
+
+#[get("/moderation/log?<page>")]
+pub fn get_moderation_log(
+    _moderator: Authorized<Moderator>,
+    state: &State<AppState>,
+    page: i64,
+) -> Result<Json<ApiResponseWithData<Page<ModerationLog>>>, Error> {
+    let data = state.database.get_moderation_log(page)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
 }
 
 #[get("/events/<event_id>/average")]
 pub fn get_average_rating_for_event(
     state: &State<AppState>,
     event_id: i32,
-) -> Result<Json<ApiResponseWithBigDecimal>, (Status, Json<ApiResponse>)> {
-    match state.database.get_average_rating_for_event(event_id) {
-        Ok(score) => match score {
-            Some(data) => Ok(Json(ApiResponseWithBigDecimal {
-                message: "Operation done.".to_string(),
-                data,
-            })),
-            None => Err((
-                Status::UnprocessableEntity,
-                Json(ApiResponse {
-                    message: "This entry does not have any rating for now.".to_string(),
-                }),
-            )),
-        },
-        Err(_) => Err((
-            Status::UnprocessableEntity,
-            Json(ApiResponse {
-                message: "Could not get the data.".to_string(),
-            }),
-        )),
+) -> Result<Json<ApiResponseWithBigDecimal>, Error> {
+    match state.database.get_average_rating_for_event(event_id)? {
+        Some(data) => Ok(Json(ApiResponseWithBigDecimal {
+            message: "Operation done.".to_string(),
+            data,
+        })),
+        None => Err(Error::NoRatingYet),
     }
 }
 
@@ -254,25 +626,1208 @@ pub fn get_average_rating_for_event(
 pub fn get_average_rating_for_match(
     state: &State<AppState>,
     match_id: i32,
-) -> Result<Json<ApiResponseWithBigDecimal>, (Status, Json<ApiResponse>)> {
-    match state.database.get_average_rating_for_match(match_id) {
-        Ok(score) => match score {
-            Some(data) => Ok(Json(ApiResponseWithBigDecimal {
-                message: "Operation done.".to_string(),
-                data,
-            })),
-            None => Err((
-                Status::UnprocessableEntity,
-                Json(ApiResponse {
-                    message: "This entry does not have any rating for now.".to_string(),
-                }),
-            )),
+) -> Result<Json<ApiResponseWithBigDecimal>, Error> {
+    match state.database.get_average_rating_for_match(match_id)? {
+        Some(data) => Ok(Json(ApiResponseWithBigDecimal {
+            message: "Operation done.".to_string(),
+            data,
+        })),
+        None => Err(Error::NoRatingYet),
+    }
+}
+
+#[get("/promotions?<page>")]
+pub fn get_promotions(
+    state: &State<AppState>,
+    page: i64,
+) -> Result<Json<ApiResponseWithData<Page<Promotion>>>, Error> {
+    let data = state.database.get_promotions(page)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+#[get("/promotions/<promotion_id>")]
+pub fn get_promotion(
+    state: &State<AppState>,
+    promotion_id: i32,
+) -> Result<Json<ApiResponseWithData<Promotion>>, Error> {
+    let data = state.database.get_promotion_by(promotion_id)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+#[get("/promotions/<promotion_id>/events?<page>")]
+pub fn get_promotion_events(
+    state: &State<AppState>,
+    promotion_id: i32,
+    page: i64,
+) -> Result<Json<ApiResponseWithData<Page<Event>>>, Error> {
+    // a missing promotion is not the same as a promotion without events
+    state.database.get_promotion_by(promotion_id)?;
+    let data = state
+        .database
+        .get_events_for_promotion(promotion_id, page)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+#[get("/promotions/<promotion_id>/average")]
+pub fn get_average_rating_for_promotion(
+    state: &State<AppState>,
+    promotion_id: i32,
+) -> Result<Json<ApiResponseWithBigDecimal>, Error> {
+    match state
+        .database
+        .get_average_rating_for_promotion(promotion_id)?
+    {
+        Some(data) => Ok(Json(ApiResponseWithBigDecimal {
+            message: "Operation done.".to_string(),
+            data,
+        })),
+        None => Err(Error::NoRatingYet),
+    }
+}
+
+#[post("/promotions", format = "application/json", data = "<promotion>")]
+pub fn add_promotion(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    promotion: Json<NewPromotion>,
+) -> Result<Json<ApiResponseWithData<Promotion>>, Error> {
+    promotion.validate()?;
+    let data = state.database.new_promotion(promotion.0)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Promotion added".to_string(),
+        data,
+    }))
+}
+
+#[put(
+    "/promotions/<promotion_id>",
+    format = "application/json",
+    data = "<promotion>"
+)]
+pub fn update_promotion(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    promotion_id: i32,
+    promotion: Json<NewPromotion>,
+) -> Result<Json<ApiResponseWithData<Promotion>>, Error> {
+    promotion.validate()?;
+    let data = state.database.update_promotion(promotion_id, promotion.0)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Promotion updated".to_string(),
+        data,
+    }))
+}
+
+/// Merges two promotions written differently, like "AEW" and "All Elite Wrestling"
+#[post(
+    "/promotions/<promotion_id>/merge",
+    format = "application/json",
+    data = "<merge>"
+)]
+pub fn merge_promotions(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    promotion_id: i32,
+    merge: Json<RequestPromotionMerge>,
+) -> Result<Json<ApiResponseWithData<Promotion>>, Error> {
+    let data = state
+        .database
+        .merge_promotions(promotion_id, merge.merged_id)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Promotions merged".to_string(),
+        data,
+    }))
+}
+
+#[get("/series?<page>")]
+pub fn get_series(
+    state: &State<AppState>,
+    page: i64,
+) -> Result<Json<ApiResponseWithData<Page<EventSeries>>>, Error> {
+    let data = state.database.get_series(page)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+#[get("/series/<series_id>")]
+pub fn get_one_series(
+    state: &State<AppState>,
+    series_id: i32,
+) -> Result<Json<ApiResponseWithData<EventSeries>>, Error> {
+    let data = state.database.get_series_by(series_id)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+/// The result of the best matches is only given to the clients asking for spoilers
+#[get("/series/<series_id>/editions?<spoilers>")]
+pub fn get_series_editions(
+    state: &State<AppState>,
+    series_id: i32,
+    spoilers: Option<bool>,
+) -> Result<Json<ApiResponseWithData<Vec<SeriesEdition>>>, Error> {
+    // a missing series is not the same as a series without editions
+    state.database.get_series_by(series_id)?;
+
+    let mut data = Vec::new();
+    for event in state.database.get_events_for_series(series_id)? {
+        let average_score = state.database.get_average_rating_for_event(event.id())?;
+        let mut best_match = state.database.get_best_match_for_event(event.id())?;
+        if !spoilers.unwrap_or(false) {
+            best_match.iter_mut().for_each(RatedMatch::hide_result);
+        }
+        data.push(SeriesEdition {
+            event,
+            average_score,
+            best_match,
+        });
+    }
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+#[post("/series", format = "application/json", data = "<series>")]
+pub fn add_series(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    series: Json<NewEventSeries>,
+) -> Result<Json<ApiResponseWithData<EventSeries>>, Error> {
+    series.validate()?;
+    let data = state.database.new_series(series.0)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Series added".to_string(),
+        data,
+    }))
+}
+
+#[put("/series/<series_id>", format = "application/json", data = "<series>")]
+pub fn update_series(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    series_id: i32,
+    series: Json<NewEventSeries>,
+) -> Result<Json<ApiResponseWithData<EventSeries>>, Error> {
+    series.validate()?;
+    let data = state.database.update_series(series_id, series.0)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Series updated".to_string(),
+        data,
+    }))
+}
+
+#[get("/venues?<page>")]
+pub fn get_venues(
+    state: &State<AppState>,
+    page: i64,
+) -> Result<Json<ApiResponseWithData<Page<Venue>>>, Error> {
+    let data = state.database.get_venues(page)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+/// Venues ranked by the average score of the events held there
+#[get("/venues/best?<page>")]
+pub fn get_best_venues(
+    state: &State<AppState>,
+    page: i64,
+) -> Result<Json<ApiResponseWithData<Page<RatedVenue>>>, Error> {
+    let data = state.database.get_best_venues(page)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+#[get("/venues/<venue_id>")]
+pub fn get_venue(
+    state: &State<AppState>,
+    venue_id: i32,
+) -> Result<Json<ApiResponseWithData<Venue>>, Error> {
+    let data = state.database.get_venue_by(venue_id)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+#[get("/venues/<venue_id>/events?<page>")]
+pub fn get_venue_events(
+    state: &State<AppState>,
+    venue_id: i32,
+    page: i64,
+) -> Result<Json<ApiResponseWithData<Page<RatedEvent>>>, Error> {
+    // a missing venue is not the same as a venue without events
+    state.database.get_venue_by(venue_id)?;
+    let data = state.database.get_events_for_venue(venue_id, page)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+#[post("/venues", format = "application/json", data = "<venue>")]
+pub fn add_venue(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    venue: Json<NewVenue>,
+) -> Result<Json<ApiResponseWithData<Venue>>, Error> {
+    venue.validate()?;
+    let data = state.database.new_venue(venue.0)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Venue added".to_string(),
+        data,
+    }))
+}
+
+#[put("/venues/<venue_id>", format = "application/json", data = "<venue>")]
+pub fn update_venue(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    venue_id: i32,
+    venue: Json<NewVenue>,
+) -> Result<Json<ApiResponseWithData<Venue>>, Error> {
+    venue.validate()?;
+    let data = state.database.update_venue(venue_id, venue.0)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Venue updated".to_string(),
+        data,
+    }))
+}
+
+#[get("/languages")]
+pub fn get_languages(
+    state: &State<AppState>,
+) -> Result<Json<ApiResponseWithData<Vec<LanguageInfo>>>, Error> {
+    let data = state.database.get_languages()?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+/// The new language can be used right away for the descriptions, labels and ratings
+#[post("/languages", format = "application/json", data = "<language>")]
+pub fn add_language(
+    _admin: Authorized<Admin>,
+    state: &State<AppState>,
+    language: Json<LanguageInfo>,
+) -> Result<Json<ApiResponseWithData<LanguageInfo>>, Error> {
+    language.validate()?;
+
+    let LanguageInfo {
+        code,
+        name,
+        native_name,
+        short_code,
+    } = language.0;
+    let data = state.database.new_language(LanguageInfo {
+        code,
+        name: name.trim().to_string(),
+        native_name: native_name.trim().to_string(),
+        short_code: short_code.map(|short_code| short_code.trim().to_lowercase()),
+    })?;
+    Ok(Json(ApiResponseWithData {
+        message: "Language added".to_string(),
+        data,
+    }))
+}
+
+#[get("/match-types?<lang>")]
+pub fn get_match_types(
+    state: &State<AppState>,
+    lang: Language,
+) -> Result<Json<ApiResponseWithData<Vec<LocalizedMatchType>>>, Error> {
+    validation::validate_language(lang, "lang", &state.database)?;
+    let data = state.database.get_match_types(lang)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+#[get("/match-types/<match_type_id>/average")]
+pub fn get_average_rating_for_match_type(
+    state: &State<AppState>,
+    match_type_id: i32,
+) -> Result<Json<ApiResponseWithBigDecimal>, Error> {
+    match state
+        .database
+        .get_average_rating_for_match_type(match_type_id)?
+    {
+        Some(data) => Ok(Json(ApiResponseWithBigDecimal {
+            message: "Operation done.".to_string(),
+            data,
+        })),
+        None => Err(Error::NoRatingYet),
+    }
+}
+
+#[post("/match-types", format = "application/json", data = "<match_type>")]
+pub fn add_match_type(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    match_type: Json<RequestMatchType>,
+) -> Result<Json<ApiResponseWithData<MatchType>>, Error> {
+    match_type.validate(&state.database)?;
+
+    let RequestMatchType { code, labels } = match_type.0;
+    let data = state
+        .database
+        .new_match_type(NewMatchType { code }, labels)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Match type added".to_string(),
+        data,
+    }))
+}
+
+#[get("/championships?<page>")]
+pub fn get_championships(
+    state: &State<AppState>,
+    page: i64,
+) -> Result<Json<ApiResponseWithData<Page<Championship>>>, Error> {
+    let data = state.database.get_championships(page)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+#[get("/championships/<championship_id>")]
+pub fn get_championship(
+    state: &State<AppState>,
+    championship_id: i32,
+) -> Result<Json<ApiResponseWithData<Championship>>, Error> {
+    let data = state.database.get_championship_by(championship_id)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+#[get("/championships/<championship_id>/history")]
+pub fn get_title_history(
+    state: &State<AppState>,
+    championship_id: i32,
+) -> Result<Json<ApiResponseWithData<TitleHistory>>, Error> {
+    let data = state.database.get_title_history(championship_id)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+#[post("/championships", format = "application/json", data = "<championship>")]
+pub fn add_championship(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    championship: Json<NewChampionship>,
+) -> Result<Json<ApiResponseWithData<Championship>>, Error> {
+    championship.validate()?;
+    let data = state.database.new_championship(championship.0)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Championship added".to_string(),
+        data,
+    }))
+}
+
+#[put(
+    "/championships/<championship_id>",
+    format = "application/json",
+    data = "<championship>"
+)]
+pub fn update_championship(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    championship_id: i32,
+    championship: Json<NewChampionship>,
+) -> Result<Json<ApiResponseWithData<Championship>>, Error> {
+    championship.validate()?;
+    let data = state
+        .database
+        .update_championship(championship_id, championship.0)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Championship updated".to_string(),
+        data,
+    }))
+}
+
+#[post(
+    "/championships/<championship_id>/reigns",
+    format = "application/json",
+    data = "<reign>"
+)]
+pub fn add_title_reign(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    championship_id: i32,
+    reign: Json<RequestReign>,
+) -> Result<Json<ApiResponseWithData<TitleReign>>, Error> {
+    reign.validate()?;
+    state.database.get_championship_by(championship_id)?;
+
+    let RequestReign {
+        worker_id,
+        start_date,
+        end_date,
+        won_match_id,
+    } = reign.0;
+    let data = state.database.new_title_reign(NewTitleReign {
+        championship_id,
+        worker_id,
+        start_date,
+        end_date,
+        won_match_id,
+    })?;
+    Ok(Json(ApiResponseWithData {
+        message: "Reign added".to_string(),
+        data,
+    }))
+}
+
+#[delete("/championships/<championship_id>/reigns/<reign_id>")]
+pub fn delete_title_reign(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    championship_id: i32,
+    reign_id: i32,
+) -> Result<Json<ApiResponse>, Error> {
+    state
+        .database
+        .delete_title_reign(championship_id, reign_id)?;
+    Ok(Json(ApiResponse {
+        message: "Reign deleted".to_string(),
+    }))
+}
+
+#[post("/events", format = "application/json", data = "<event>")]
+pub fn add_event(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    event: Json<NewEvent>,
+) -> Result<Json<ApiResponseWithData<Event>>, Error> {
+    event.validate(&state.database)?;
+
+    let mut event = event.0;
+    event.name = event.name.trim().to_string();
+    let data = state.database.new_event(event)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Event added".to_string(),
+        data,
+    }))
+}
+
+#[put("/events/<event_id>", format = "application/json", data = "<event>")]
+pub fn update_event(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    event_id: i32,
+    event: Json<NewEvent>,
+) -> Result<Json<ApiResponseWithData<Event>>, Error> {
+    event.validate(&state.database)?;
+
+    let mut event = event.0;
+    event.name = event.name.trim().to_string();
+    let data = state.database.update_event(event_id, event)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Event updated".to_string(),
+        data,
+    }))
+}
+
+#[patch("/events/<event_id>", format = "application/json", data = "<changes>")]
+pub fn patch_event(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    event_id: i32,
+    changes: Json<EventChanges>,
+) -> Result<Json<ApiResponseWithData<Event>>, Error> {
+    changes.validate(&state.database)?;
+
+    let mut changes = changes.0;
+    changes.name = changes.name.map(|name| name.trim().to_string());
+    let data = state.database.patch_event(event_id, changes)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Event updated".to_string(),
+        data,
+    }))
+}
+
+#[put(
+    "/events/<event_id>/card",
+    format = "application/json",
+    data = "<order>"
+)]
+pub fn reorder_card(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    event_id: i32,
+    order: Json<RequestCardOrder>,
+) -> Result<Json<ApiResponseWithData<Vec<MatchDetails>>>, Error> {
+    let data = state.database.reorder_card(event_id, order.0.match_ids)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Card reordered".to_string(),
+        data,
+    }))
+}
+
+#[delete("/events/<event_id>")]
+pub fn delete_event(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    event_id: i32,
+) -> Result<Json<ApiResponse>, Error> {
+    state.database.delete_event(event_id)?;
+    Ok(Json(ApiResponse {
+        message: "Event deleted".to_string(),
+    }))
+}
+
+#[post("/match", format = "application/json", data = "<match_obj>")]
+pub fn add_match(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    match_obj: Json<RequestMatch>,
+) -> Result<Json<ApiResponseWithData<MatchDetails>>, Error> {
+    let RequestMatch {
+        event_id,
+        workers,
+        segment,
+        descriptions,
+    } = match_obj.0;
+    let new_match = NewMatch {
+        event_id,
+        workers,
+        segment,
+    };
+    new_match.validate(&state.database)?;
+    for (i, description) in descriptions.iter().enumerate() {
+        validation::validate_language(
+            description.language_code,
+            &format!("descriptions[{}].language_code", i),
+            &state.database,
+        )?;
+    }
+    let data = state.database.new_match(new_match, descriptions)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Match added".to_string(),
+        data,
+    }))
+}
+
+#[put("/match/<match_id>", format = "application/json", data = "<match_obj>")]
+pub fn update_match(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    match_id: i32,
+    match_obj: Json<NewMatch>,
+) -> Result<Json<ApiResponseWithData<MatchDetails>>, Error> {
+    match_obj.validate(&state.database)?;
+    let data = state.database.update_match(match_id, match_obj.0)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Match updated".to_string(),
+        data,
+    }))
+}
+
+#[patch("/match/<match_id>", format = "application/json", data = "<changes>")]
+pub fn patch_match(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    match_id: i32,
+    changes: Json<MatchChanges>,
+) -> Result<Json<ApiResponseWithData<MatchDetails>>, Error> {
+    changes.validate(&state.database)?;
+    let data = state.database.patch_match(match_id, changes.0)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Match updated".to_string(),
+        data,
+    }))
+}
+
+#[put(
+    "/match/<match_id>/result",
+    format = "application/json",
+    data = "<result>"
+)]
+pub fn set_match_result(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    match_id: i32,
+    result: Json<MatchResult>,
+) -> Result<Json<ApiResponseWithData<MatchDetails>>, Error> {
+    let match_obj = state.database.get_match_by(match_id)?;
+    result.validate(match_obj.workers())?;
+    let data = state.database.set_match_result(match_id, result.0)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Match result updated".to_string(),
+        data,
+    }))
+}
+
+#[get("/match/<match_id>/types?<lang>")]
+pub fn get_match_types_for_match(
+    state: &State<AppState>,
+    match_id: i32,
+    lang: Language,
+) -> Result<Json<ApiResponseWithData<Vec<LocalizedMatchType>>>, Error> {
+    validation::validate_language(lang, "lang", &state.database)?;
+    let data = state.database.get_types_for_match(match_id, lang)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+#[put(
+    "/match/<match_id>/types",
+    format = "application/json",
+    data = "<match_types>"
+)]
+pub fn set_match_types(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    match_id: i32,
+    match_types: Json<RequestMatchTypes>,
+) -> Result<Json<ApiResponse>, Error> {
+    state
+        .database
+        .set_match_types(match_id, match_types.0.match_type_ids)?;
+    Ok(Json(ApiResponse {
+        message: "Match types updated".to_string(),
+    }))
+}
+
+/// Whether a title changed hands is only given to the clients asking for spoilers
+#[get("/match/<match_id>/championships?<spoilers>")]
+pub fn get_contested_titles(
+    state: &State<AppState>,
+    match_id: i32,
+    spoilers: Option<bool>,
+) -> Result<Json<ApiResponseWithData<Vec<ContestedTitle>>>, Error> {
+    let mut data = state.database.get_contested_titles(match_id)?;
+    if !spoilers.unwrap_or(false) {
+        data.iter_mut().for_each(ContestedTitle::hide_result);
+    }
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+#[put(
+    "/match/<match_id>/championships/<championship_id>",
+    format = "application/json",
+    data = "<title_match>"
+)]
+pub fn set_title_match(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    match_id: i32,
+    championship_id: i32,
+    title_match: Json<RequestTitleMatch>,
+) -> Result<Json<ApiResponseWithData<Vec<ContestedTitle>>>, Error> {
+    state.database.get_match_by(match_id)?;
+    state.database.get_championship_by(championship_id)?;
+    state.database.set_title_match(NewTitleMatch {
+        match_id,
+        championship_id,
+        title_changed: title_match.title_changed,
+    })?;
+    let data = state.database.get_contested_titles(match_id)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Title match updated".to_string(),
+        data,
+    }))
+}
+
+#[delete("/match/<match_id>/championships/<championship_id>")]
+pub fn delete_title_match(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    match_id: i32,
+    championship_id: i32,
+) -> Result<Json<ApiResponse>, Error> {
+    state
+        .database
+        .delete_title_match(match_id, championship_id)?;
+    Ok(Json(ApiResponse {
+        message: "Title match deleted".to_string(),
+    }))
+}
+
+#[delete("/match/<match_id>")]
+pub fn delete_match(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    match_id: i32,
+) -> Result<Json<ApiResponse>, Error> {
+    state.database.delete_match(match_id)?;
+    Ok(Json(ApiResponse {
+        message: "Match deleted".to_string(),
+    }))
+}
+
+#[post(
+    "/match/<match_id>/descriptions",
+    format = "application/json",
+    data = "<description>"
+)]
+pub fn add_match_desc(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    match_id: i32,
+    description: Json<LocalizedDescription>,
+) -> Result<Json<ApiResponseWithData<MatchDesc>>, Error> {
+    validation::validate_language(description.language_code, "language_code", &state.database)?;
+    state.database.get_match_by(match_id)?;
+    let data = state.database.new_match_description(NewMatchDesc {
+        match_id,
+        description: description.0.description,
+        language_code: description.0.language_code,
+    })?;
+    Ok(Json(ApiResponseWithData {
+        message: "Description added".to_string(),
+        data,
+    }))
+}
+
+#[put(
+    "/match/<match_id>/description?<lang>",
+    format = "application/json",
+    data = "<description>"
+)]
+pub fn update_match_desc(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    match_id: i32,
+    lang: Language,
+    description: Json<RequestDescription>,
+) -> Result<Json<ApiResponseWithData<MatchDesc>>, Error> {
+    validation::validate_language(lang, "lang", &state.database)?;
+    let data =
+        state
+            .database
+            .update_match_description(match_id, lang, description.0.description)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Description updated".to_string(),
+        data,
+    }))
+}
+
+#[delete("/match/<match_id>/description?<lang>")]
+pub fn delete_match_desc(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    match_id: i32,
+    lang: Language,
+) -> Result<Json<ApiResponse>, Error> {
+    validation::validate_language(lang, "lang", &state.database)?;
+    state.database.delete_match_description(match_id, lang)?;
+    Ok(Json(ApiResponse {
+        message: "Description deleted".to_string(),
+    }))
+}
+
+/// Workers are found by their name or one of their aliases, whatever the case and spacing
+#[get("/workers/<name>?<spoilers>")]
+pub fn get_worker(
+    state: &State<AppState>,
+    name: &str,
+    spoilers: Option<bool>,
+) -> Result<Json<ApiResponseWithData<WorkerProfile>>, Error> {
+    let worker = state.database.get_worker_by_name(name)?;
+    let worker_id = worker.id();
+    let mut data = WorkerProfile {
+        worker,
+        aliases: state.database.get_worker_aliases(worker_id)?,
+        match_count: state.database.get_match_count_for_worker(worker_id)?,
+        rating_count: state.database.get_rating_count_for_worker(worker_id)?,
+        average_score: state.database.get_average_rating_for_worker(worker_id)?,
+        best_match: state.database.get_best_match_for_worker(worker_id)?,
+        worst_match: state.database.get_worst_match_for_worker(worker_id)?,
+    };
+    if !spoilers.unwrap_or(false) {
+        data.best_match.iter_mut().for_each(RatedMatch::hide_result);
+        data.worst_match
+            .iter_mut()
+            .for_each(RatedMatch::hide_result);
+    }
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+#[get("/workers/<name>/matches?<page>&<spoilers>")]
+pub fn get_worker_matches(
+    state: &State<AppState>,
+    name: &str,
+    page: i64,
+    spoilers: Option<bool>,
+) -> Result<Json<ApiResponseWithData<Page<MatchDetails>>>, Error> {
+    let worker = state.database.get_worker_by_name(name)?;
+    let mut data = state.database.get_matches_for_worker(worker.id(), page)?;
+    if !spoilers.unwrap_or(false) {
+        data.items.iter_mut().for_each(MatchDetails::hide_result);
+    }
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+#[get("/workers/<worker_id>/aliases")]
+pub fn get_worker_aliases(
+    state: &State<AppState>,
+    worker_id: i32,
+) -> Result<Json<ApiResponseWithData<Vec<WorkerAlias>>>, Error> {
+    let data = state.database.get_worker_aliases(worker_id)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+/// A worker created under this name, like a ring name written differently on another card, is
+/// merged into this one
+#[post(
+    "/workers/<worker_id>/aliases",
+    format = "application/json",
+    data = "<alias>"
+)]
+pub fn add_worker_alias(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    worker_id: i32,
+    alias: Json<RequestAlias>,
+) -> Result<Json<ApiResponseWithData<WorkerAlias>>, Error> {
+    alias.validate()?;
+
+    let RequestAlias {
+        name,
+        used_from,
+        used_until,
+    } = alias.0;
+    let data = state.database.new_worker_alias(NewWorkerAlias {
+        worker_id,
+        name: workers::normalize_name(&name),
+        used_from,
+        used_until,
+    })?;
+    Ok(Json(ApiResponseWithData {
+        message: "Alias added".to_string(),
+        data,
+    }))
+}
+
+#[delete("/workers/<worker_id>/aliases/<alias_id>")]
+pub fn delete_worker_alias(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    worker_id: i32,
+    alias_id: i32,
+) -> Result<Json<ApiResponse>, Error> {
+    state.database.delete_worker_alias(worker_id, alias_id)?;
+    Ok(Json(ApiResponse {
+        message: "Alias deleted".to_string(),
+    }))
+}
+
+/// Biggest document accepted by the import, a whole card is usually a few kilobytes
+const IMPORT_LIMIT_MEBIBYTES: u64 = 8;
+
+#[post("/import?<dry_run>", data = "<document>")]
+pub async fn import_cards(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    content_type: &ContentType,
+    dry_run: Option<bool>,
+    document: Data<'_>,
+) -> Result<Json<ApiResponseWithData<ImportReport>>, Error> {
+    let format = match ImportFormat::from_extension(content_type.sub().as_str()) {
+        Some(format) => format,
+        None => {
+            return Err(Error::InvalidData {
+                fields: vec![FieldError::new(
+                    "content_type",
+                    "unsupported",
+                    "The document must be sent as application/json or text/csv.",
+                )],
+            });
+        }
+    };
+
+    let document = match document
+        .open(IMPORT_LIMIT_MEBIBYTES.mebibytes())
+        .into_string()
+        .await
+    {
+        Ok(document) if document.is_complete() => document.into_inner(),
+        Ok(_) => {
+            return Err(Error::InvalidData {
+                fields: vec![FieldError::new(
+                    "document",
+                    "too_large",
+                    format!(
+                        "The document must not exceed {} MiB.",
+                        IMPORT_LIMIT_MEBIBYTES
+                    ),
+                )],
+            });
+        }
+        Err(err) => return Err(Error::INEEDTOLOG { e: err.to_string() }),
+    };
+
+    let cards = import::parse(&document, format)?;
+    let dry_run = dry_run.unwrap_or(false);
+    let data = state.database.import_cards(cards, dry_run)?;
+    Ok(Json(ApiResponseWithData {
+        message: if dry_run {
+            "Nothing imported, dry run".to_string()
+        } else {
+            "Cards imported".to_string()
+        },
+        data,
+    }))
+}
+
+/// Number of cards waiting to be sent to the client while the next ones are read
+const EXPORT_BUFFER_SIZE: usize = 16;
+
+#[get("/export?<format>&<promotion>&<from>&<to>")]
+pub async fn export_cards(
+    _editor: Authorized<Editor>,
+    state: &State<AppState>,
+    format: Option<&str>,
+    promotion: Option<String>,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<(ContentType, TextStream![String]), Error> {
+    let format = match format {
+        Some(format) => ExportFormat::from_name(format)?,
+        None => ExportFormat::JsonLines,
+    };
+    let filter = ExportFilter::new(promotion, from, to)?;
+
+    // the database is read on a blocking thread, and the cards are streamed as they come
+    let database = state.database.clone();
+    let (sender, mut receiver) = mpsc::channel::<Result<String, Error>>(EXPORT_BUFFER_SIZE);
+    task::spawn_blocking(move || {
+        let mut writer = ExportWriter::new(format);
+        let result = database.export_cards(&filter, &mut |card| {
+            let text = writer.write(card)?;
+            sender
+                .blocking_send(Ok(text))
+                .map_err(|_| Error::INEEDTOLOG {
+                    e: "the client stopped receiving the export".to_string(),
+                })
+        });
+
+        if let Err(err) = result {
+            rocket::warn!("Export interrupted: {}", err);
+            // nobody to tell if the client is the one who stopped
+            let _ = sender.blocking_send(Err(err));
+        }
+    });
+
+    // until the first card is read, an error still gets its own status instead of a 200
+    let first = match receiver.recv().await {
+        Some(Ok(text)) => Some(text),
+        Some(Err(err)) => return Err(err),
+        None => None,
+    };
+
+    Ok((
+        format.content_type(),
+        TextStream! {
+            if let Some(text) = first {
+                yield text;
+            }
+            while let Some(result) = receiver.recv().await {
+                match result {
+                    Ok(text) => yield text,
+                    Err(err) => {
+                        yield export::interruption(&err);
+                        break;
+                    }
+                }
+            }
         },
-        Err(_) => Err((
-            Status::UnprocessableEntity,
-            Json(ApiResponse {
-                message: "Could not get the data.".to_string(),
-            }),
-        )),
+    ))
+}
+
+#[post("/auth/register", format = "application/json", data = "<account>")]
+pub fn register(
+    state: &State<AppState>,
+    account: Json<RequestAccount>,
+) -> Result<Json<ApiResponseWithData<SessionToken>>, Error> {
+    account.validate()?;
+
+    let user = state.database.new_user(NewUser {
+        username: account.username.trim().to_string(),
+        password_hash: auth::hash_password(&account.password)?,
+        creation_date: Utc::now().naive_utc(),
+    })?;
+    let data = auth::new_session(&state.database, user)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Account created".to_string(),
+        data,
+    }))
+}
+
+#[post("/auth/login", format = "application/json", data = "<account>")]
+pub fn login(
+    state: &State<AppState>,
+    account: Json<RequestAccount>,
+) -> Result<Json<ApiResponseWithData<SessionToken>>, Error> {
+    let user = match state.database.get_user_by_username(account.username.trim()) {
+        Ok(user) => user,
+        // no difference between an unknown user and a wrong password
+        Err(Error::ResourceDoesNotExists) => return Err(Error::InvalidCredentials),
+        Err(err) => return Err(err),
+    };
+
+    if !auth::verify_password(&account.password, user.password_hash())? {
+        return Err(Error::InvalidCredentials);
     }
+
+    let data = auth::new_session(&state.database, user)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Logged in".to_string(),
+        data,
+    }))
+}
+
+#[post("/auth/logout")]
+pub fn logout(
+    authenticated: AuthenticatedUser,
+    state: &State<AppState>,
+) -> Result<Json<ApiResponse>, Error> {
+    state.database.delete_session(&authenticated.token_hash)?;
+    Ok(Json(ApiResponse {
+        message: "Logged out".to_string(),
+    }))
+}
+
+#[get("/users/me")]
+pub fn get_me(authenticated: AuthenticatedUser) -> Json<ApiResponseWithData<User>> {
+    Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data: authenticated.user,
+    })
+}
+
+/// The anonymous ratings made before accounts existed only go to the account once a moderator
+/// approved the claim, whatever the username of the account
+#[post("/users/me/claims", format = "application/json", data = "<claim>")]
+pub fn claim_ratings(
+    authenticated: AuthenticatedUser,
+    state: &State<AppState>,
+    claim: Json<RequestClaim>,
+) -> Result<Json<ApiResponseWithData<RatingClaim>>, Error> {
+    claim.validate()?;
+
+    let data = state.database.new_rating_claim(NewRatingClaim {
+        user_id: authenticated.user.id(),
+        username: claim.username.trim().to_string(),
+        proof: claim.0.proof.trim().to_string(),
+        creation_date: Utc::now().naive_utc(),
+    })?;
+    Ok(Json(ApiResponseWithData {
+        message: "Claim sent to the moderators".to_string(),
+        data,
+    }))
+}
+
+#[get("/moderation/claims?<page>")]
+pub fn get_rating_claims(
+    _moderator: Authorized<Moderator>,
+    state: &State<AppState>,
+    page: i64,
+) -> Result<Json<ApiResponseWithData<Page<RatingClaim>>>, Error> {
+    let data = state.database.get_pending_rating_claims(page)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+#[post(
+    "/moderation/claims/<claim_id>",
+    format = "application/json",
+    data = "<decision>"
+)]
+pub fn resolve_rating_claim(
+    moderator: Authorized<Moderator>,
+    state: &State<AppState>,
+    claim_id: i32,
+    decision: Json<RequestClaimDecision>,
+) -> Result<Json<ApiResponseWithData<RatingClaim>>, Error> {
+    let data = state
+        .database
+        .resolve_rating_claim(claim_id, decision.approved, &moderator.user)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Claim resolved".to_string(),
+        data,
+    }))
+}
+
+/// Every rating of the account, with the hidden ones
+#[get("/users/me/ratings?<page>")]
+pub fn get_my_ratings(
+    authenticated: AuthenticatedUser,
+    state: &State<AppState>,
+    page: i64,
+) -> Result<Json<ApiResponseWithData<Page<Rating>>>, Error> {
+    let data = state
+        .database
+        .get_ratings_for_user(authenticated.user.id(), page)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+#[get("/users?<page>")]
+pub fn get_users(
+    _admin: Authorized<Admin>,
+    state: &State<AppState>,
+    page: i64,
+) -> Result<Json<ApiResponseWithData<Page<User>>>, Error> {
+    let data = state.database.get_users(page)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Operation done".to_string(),
+        data,
+    }))
+}
+
+#[put("/users/<user_id>/role", format = "application/json", data = "<role>")]
+pub fn set_user_role(
+    _admin: Authorized<Admin>,
+    state: &State<AppState>,
+    user_id: i32,
+    role: Json<RequestRole>,
+) -> Result<Json<ApiResponseWithData<User>>, Error> {
+    let data = state.database.set_user_role(user_id, role.0.role)?;
+    Ok(Json(ApiResponseWithData {
+        message: "Role changed".to_string(),
+        data,
+    }))
 }
@@ -4,49 +4,170 @@ use diesel::{
     backend::Backend,
     deserialize::{self, FromSql, FromSqlRow},
     expression::AsExpression,
-    prelude::{Insertable, Queryable},
+    prelude::{AsChangeset, Insertable, Queryable},
     serialize::ToSql,
     sql_types::VarChar,
 };
-use rocket::FromFormField;
-use serde::{Deserialize, Serialize};
+use rocket::form::{self, FromFormField, ValueField};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use std::{fmt, str, str::FromStr};
 
-use crate::schema::{event, match_, match_desc, rating};
+use crate::{
+    pagination::Page,
+    schema::{
+        championship, event, event_series, language, match_, match_classification, match_desc,
+        match_participant, match_type, match_type_label, moderation_log, promotion, rating,
+        rating_claim, rating_flag, rating_revision, session, title_match, title_reign, users,
+        venue, worker, worker_alias,
+    },
+};
+
+/// based on ISO 3 letter representation of languages for DB and API representation. Any code
+/// can be parsed, the known languages are the ones of the `language` table.
+#[derive(AsExpression, Clone, Debug, Copy, PartialEq, Eq, Hash, FromSqlRow)]
+#[diesel(sql_type = VarChar)]
+pub struct Language([u8; 3]);
+
+impl Language {
+    pub const ENGLISH: Language = Language(*b"ENG");
+
+    pub fn as_str(&self) -> &str {
+        // only made of ASCII letters
+        str::from_utf8(&self.0).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Codes are three letters, written in uppercase whatever the case they were given in
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        match code.as_bytes() {
+            &[a, b, c] if code.chars().all(|c| c.is_ascii_alphabetic()) => Ok(Language([
+                a.to_ascii_uppercase(),
+                b.to_ascii_uppercase(),
+                c.to_ascii_uppercase(),
+            ])),
+            _ => Err(format!("Unrecognized language {}", code)),
+        }
+    }
+}
+
+impl Serialize for Language {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Language {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        code.parse().map_err(de::Error::custom)
+    }
+}
+
+impl<'v> FromFormField<'v> for Language {
+    fn from_value(field: ValueField<'v>) -> form::Result<'v, Self> {
+        Ok(field.value.parse().map_err(form::Error::validation)?)
+    }
+}
+
+/// Implement conversion from DB types (string literals) into Language
+impl<DB> FromSql<VarChar, DB> for Language
+where
+    DB: Backend,
+    String: FromSql<VarChar, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        let value = String::from_sql(bytes)?;
+        Ok(value.parse()?)
+    }
+}
+
+/// Implement conversion from Language to DB type for inserting new matches or ratings
+impl<DB> ToSql<VarChar, DB> for Language
+where
+    DB: Backend,
+    str: ToSql<VarChar, DB>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, DB>,
+    ) -> diesel::serialize::Result {
+        self.as_str().to_sql(out)
+    }
+}
 
-/// based on ISO 3 letter representation of languages for DB and API representation
+/// Language that can be used for the descriptions, labels and ratings
+#[derive(Insertable, Queryable, Serialize, Deserialize, Clone)]
+#[diesel(table_name = language)]
+pub struct LanguageInfo {
+    pub code: Language,
+    /// In English
+    pub name: String,
+    /// In the language itself
+    pub native_name: String,
+    /// ISO 639-1 code matched against the Accept-Language header, like "fr"
+    #[serde(default)]
+    pub short_code: Option<String>,
+}
+
+/// Roles of the accounts, from the least to the most privileged. Each role can do everything
+/// the previous ones can.
 #[derive(
-    AsExpression, Serialize, Deserialize, Clone, Debug, Copy, PartialEq, FromSqlRow, FromFormField,
+    AsExpression,
+    Serialize,
+    Deserialize,
+    Clone,
+    Debug,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    FromSqlRow,
 )]
 #[diesel(sql_type = VarChar)]
-pub enum Language {
-    #[serde(rename = "FRE")]
-    // to have the same output as input
-    #[field(value = "FRE")]
-    French,
-    #[serde(rename = "ENG")]
-    // to have the same output as input
-    #[field(value = "ENG")]
-    English,
-}
-
-/// Implement conversion from DB types (string literals) into Language enum
-impl<DB> FromSql<VarChar, DB> for Language
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Can only read, for accounts that lost the right to rate
+    Reader,
+    Rater,
+    /// Manages events, matches and their descriptions
+    Editor,
+    /// Moderates the ratings
+    Moderator,
+    /// Manages the accounts
+    Admin,
+}
+
+/// Implement conversion from DB types (string literals) into Role enum
+impl<DB> FromSql<VarChar, DB> for Role
 where
     DB: Backend,
     String: FromSql<VarChar, DB>,
 {
     fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
         let value = String::from_sql(bytes)?;
-        match value {
-            _ if value == "FRE" => Ok(Language::French),
-            _ if value == "ENG" => Ok(Language::English),
-            lang => Err(format!("Unrecognized language {}", lang).into()),
+        match value.as_str() {
+            "reader" => Ok(Role::Reader),
+            "rater" => Ok(Role::Rater),
+            "editor" => Ok(Role::Editor),
+            "moderator" => Ok(Role::Moderator),
+            "admin" => Ok(Role::Admin),
+            role => Err(format!("Unrecognized role {}", role).into()),
         }
     }
 }
 
-/// Implement conversion from Language enum to DB type for inserting new matches or ratings
-impl<DB> ToSql<VarChar, DB> for Language
+/// Implement conversion from Role enum to DB type for changing the role of an account
+impl<DB> ToSql<VarChar, DB> for Role
 where
     DB: Backend,
     str: ToSql<VarChar, DB>,
@@ -56,19 +177,302 @@ where
         out: &mut diesel::serialize::Output<'b, '_, DB>,
     ) -> diesel::serialize::Result {
         match self {
-            Language::French => "FRE".to_sql(out),
-            Language::English => "ENG".to_sql(out),
+            Role::Reader => "reader".to_sql(out),
+            Role::Rater => "rater".to_sql(out),
+            Role::Editor => "editor".to_sql(out),
+            Role::Moderator => "moderator".to_sql(out),
+            Role::Admin => "admin".to_sql(out),
         }
     }
 }
 
-#[derive(Insertable, Queryable, Serialize, Clone)]
+/// What a moderator did to a rating
+#[derive(AsExpression, Serialize, Deserialize, Clone, Debug, Copy, PartialEq, FromSqlRow)]
+#[diesel(sql_type = VarChar)]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationAction {
+    /// The rating is no longer published
+    Hide,
+    /// A hidden rating is published again
+    Restore,
+    Delete,
+}
+
+/// Implement conversion from DB types (string literals) into ModerationAction enum
+impl<DB> FromSql<VarChar, DB> for ModerationAction
+where
+    DB: Backend,
+    String: FromSql<VarChar, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        let value = String::from_sql(bytes)?;
+        match value.as_str() {
+            "hide" => Ok(ModerationAction::Hide),
+            "restore" => Ok(ModerationAction::Restore),
+            "delete" => Ok(ModerationAction::Delete),
+            action => Err(format!("Unrecognized moderation action {}", action).into()),
+        }
+    }
+}
+
+/// Implement conversion from ModerationAction enum to DB type for the audit trail
+impl<DB> ToSql<VarChar, DB> for ModerationAction
+where
+    DB: Backend,
+    str: ToSql<VarChar, DB>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, DB>,
+    ) -> diesel::serialize::Result {
+        match self {
+            ModerationAction::Hide => "hide".to_sql(out),
+            ModerationAction::Restore => "restore".to_sql(out),
+            ModerationAction::Delete => "delete".to_sql(out),
+        }
+    }
+}
+
+/// How a match ended
+#[derive(AsExpression, Serialize, Deserialize, Clone, Debug, Copy, PartialEq, FromSqlRow)]
+#[diesel(sql_type = VarChar)]
+#[serde(rename_all = "snake_case")]
+pub enum Finish {
+    Pinfall,
+    Submission,
+    Disqualification,
+    CountOut,
+    /// The match was stopped without a winner
+    NoContest,
+    Draw,
+}
+
+impl Finish {
+    /// Whether a side can win a match ending this way
+    pub fn has_winner(&self) -> bool {
+        !matches!(self, Finish::NoContest | Finish::Draw)
+    }
+}
+
+/// Implement conversion from DB types (string literals) into Finish enum
+impl<DB> FromSql<VarChar, DB> for Finish
+where
+    DB: Backend,
+    String: FromSql<VarChar, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        let value = String::from_sql(bytes)?;
+        match value.as_str() {
+            "pinfall" => Ok(Finish::Pinfall),
+            "submission" => Ok(Finish::Submission),
+            "disqualification" => Ok(Finish::Disqualification),
+            "count_out" => Ok(Finish::CountOut),
+            "no_contest" => Ok(Finish::NoContest),
+            "draw" => Ok(Finish::Draw),
+            finish => Err(format!("Unrecognized finish {}", finish).into()),
+        }
+    }
+}
+
+/// Implement conversion from Finish enum to DB type
+impl<DB> ToSql<VarChar, DB> for Finish
+where
+    DB: Backend,
+    str: ToSql<VarChar, DB>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, DB>,
+    ) -> diesel::serialize::Result {
+        match self {
+            Finish::Pinfall => "pinfall".to_sql(out),
+            Finish::Submission => "submission".to_sql(out),
+            Finish::Disqualification => "disqualification".to_sql(out),
+            Finish::CountOut => "count_out".to_sql(out),
+            Finish::NoContest => "no_contest".to_sql(out),
+            Finish::Draw => "draw".to_sql(out),
+        }
+    }
+}
+
+/// Part of the show a match belongs to
+#[derive(
+    AsExpression, Serialize, Deserialize, Clone, Debug, Copy, PartialEq, Default, FromSqlRow,
+)]
+#[diesel(sql_type = VarChar)]
+#[serde(rename_all = "snake_case")]
+pub enum Segment {
+    PreShow,
+    #[default]
+    MainCard,
+    MainEvent,
+    /// Not broadcast
+    DarkMatch,
+}
+
+/// Implement conversion from DB types (string literals) into Segment enum
+impl<DB> FromSql<VarChar, DB> for Segment
+where
+    DB: Backend,
+    String: FromSql<VarChar, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        let value = String::from_sql(bytes)?;
+        match value.as_str() {
+            "pre_show" => Ok(Segment::PreShow),
+            "main_card" => Ok(Segment::MainCard),
+            "main_event" => Ok(Segment::MainEvent),
+            "dark_match" => Ok(Segment::DarkMatch),
+            segment => Err(format!("Unrecognized segment {}", segment).into()),
+        }
+    }
+}
+
+/// Implement conversion from Segment enum to DB type
+impl<DB> ToSql<VarChar, DB> for Segment
+where
+    DB: Backend,
+    str: ToSql<VarChar, DB>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, DB>,
+    ) -> diesel::serialize::Result {
+        match self {
+            Segment::PreShow => "pre_show".to_sql(out),
+            Segment::MainCard => "main_card".to_sql(out),
+            Segment::MainEvent => "main_event".to_sql(out),
+            Segment::DarkMatch => "dark_match".to_sql(out),
+        }
+    }
+}
+
+#[derive(Queryable, Serialize, Clone)]
 #[diesel(table_name = event)]
 pub struct Event {
     id: i32,
     name: String,
+    // name of the promotion, from the promotion table
     promotion: String,
     date: NaiveDate,
+    promotion_id: i32,
+    venue_id: Option<i32>,
+    attendance: Option<i32>,
+    series_id: Option<i32>,
+}
+
+/// Recurring show, like every Double or Nothing
+#[derive(Queryable, Serialize, Clone)]
+#[diesel(table_name = event_series)]
+pub struct EventSeries {
+    id: i32,
+    name: String,
+    promotion_id: Option<i32>,
+}
+
+/// Event of a series with its average score and best match
+#[derive(Serialize)]
+pub struct SeriesEdition {
+    #[serde(flatten)]
+    pub event: Event,
+    #[serde(with = "bigdecimal::serde::json_num_option")]
+    pub average_score: Option<BigDecimal>,
+    pub best_match: Option<RatedMatch>,
+}
+
+#[derive(Queryable, Serialize, Clone)]
+#[diesel(table_name = venue)]
+pub struct Venue {
+    id: i32,
+    name: String,
+    city: String,
+    country: String,
+    capacity: Option<i32>,
+}
+
+/// Event with the average score and number of the published ratings of its matches
+#[derive(Queryable, Serialize, Clone)]
+pub struct RatedEvent {
+    #[serde(flatten)]
+    event: Event,
+    // necessary to return the number as an actual number and not a string
+    #[serde(with = "bigdecimal::serde::json_num_option")]
+    average_score: Option<BigDecimal>,
+    rating_count: i64,
+}
+
+/// Venue with the average score and number of the published ratings of the events held there
+#[derive(Queryable, Serialize, Clone)]
+pub struct RatedVenue {
+    #[serde(flatten)]
+    venue: Venue,
+    #[serde(with = "bigdecimal::serde::json_num_option")]
+    average_score: Option<BigDecimal>,
+    rating_count: i64,
+}
+
+#[derive(Queryable, Serialize, Clone)]
+#[diesel(table_name = promotion)]
+pub struct Promotion {
+    id: i32,
+    name: String,
+    abbreviation: Option<String>,
+    country: Option<String>,
+    active_from: Option<i16>,
+    // none while the promotion is active
+    active_until: Option<i16>,
+}
+
+#[derive(Queryable, Serialize, Clone)]
+#[diesel(table_name = championship)]
+pub struct Championship {
+    id: i32,
+    name: String,
+    promotion_id: Option<i32>,
+}
+
+/// Time a worker held a championship
+#[derive(Queryable, Serialize, Clone)]
+#[diesel(table_name = title_reign)]
+#[diesel(belongs_to(Championship, foreign_key = championship_id))]
+pub struct TitleReign {
+    id: i32,
+    championship_id: i32,
+    worker_id: i32,
+    start_date: NaiveDate,
+    // none for the current champion
+    end_date: Option<NaiveDate>,
+    won_match_id: Option<i32>,
+}
+
+/// Championship contested in a match
+#[derive(Queryable, Serialize, Clone)]
+pub struct ContestedTitle {
+    #[serde(flatten)]
+    championship: Championship,
+    // left out in spoiler mode
+    title_changed: Option<bool>,
+}
+
+/// Reign in the history of a championship, with the published ratings of its defenses
+#[derive(Serialize)]
+pub struct ReignDetails {
+    #[serde(flatten)]
+    pub reign: TitleReign,
+    pub worker_name: String,
+    pub defense_count: i64,
+    #[serde(with = "bigdecimal::serde::json_num_option")]
+    pub average_score: Option<BigDecimal>,
+}
+
+/// Every reign of a championship, the first one first
+#[derive(Serialize)]
+pub struct TitleHistory {
+    pub championship: Championship,
+    /// Over all the defenses of the championship
+    #[serde(with = "bigdecimal::serde::json_num_option")]
+    pub average_score: Option<BigDecimal>,
+    pub reigns: Vec<ReignDetails>,
 }
 
 #[derive(Insertable, Queryable, Serialize, Clone)]
@@ -78,6 +482,148 @@ pub struct Match {
     id: i32,
     event_id: i32,
     workers: String,
+    // the result, left out in spoiler mode
+    winning_side: Option<i16>,
+    finish: Option<Finish>,
+    /// In seconds
+    duration: Option<i32>,
+    // the first match of the card is at 1
+    position: i16,
+    segment: Segment,
+}
+
+/// Stipulation of a match, like "ladder" or "tag_team"
+#[derive(Queryable, Serialize, Clone)]
+#[diesel(table_name = match_type)]
+pub struct MatchType {
+    id: i32,
+    code: String,
+}
+
+/// Match type with its label in the requested language, if it has one
+#[derive(Queryable, Serialize, Clone)]
+pub struct LocalizedMatchType {
+    #[serde(flatten)]
+    match_type: MatchType,
+    label: Option<String>,
+}
+
+/// Wrestler, or team known under its own name like "The Young Bucks"
+#[derive(Queryable, Serialize, Clone)]
+#[diesel(table_name = worker)]
+pub struct Worker {
+    id: i32,
+    name: String,
+}
+
+/// Other name of a worker. The dated ones are the ring names the worker used over time.
+#[derive(Queryable, Serialize, Clone)]
+#[diesel(table_name = worker_alias)]
+#[diesel(belongs_to(Worker, foreign_key = worker_id))]
+pub struct WorkerAlias {
+    id: i32,
+    worker_id: i32,
+    name: String,
+    used_from: Option<NaiveDate>,
+    used_until: Option<NaiveDate>,
+}
+
+/// Worker of a match, with the side it was on
+#[derive(Queryable, Serialize, Clone)]
+pub struct Participant {
+    #[serde(skip_serializing)]
+    match_id: i32,
+    side: i16,
+    worker_id: i32,
+    name: String,
+}
+
+/// Match along with its participants, parsed from its workers
+#[derive(Serialize, Clone)]
+pub struct MatchDetails {
+    #[serde(flatten)]
+    pub match_obj: Match,
+    pub participants: Vec<Participant>,
+}
+
+impl MatchDetails {
+    pub fn hide_result(&mut self) {
+        self.match_obj.hide_result();
+    }
+}
+
+/// Match with the average score and number of its published ratings
+#[derive(Queryable, Serialize, Clone)]
+pub struct RatedMatch {
+    #[serde(flatten)]
+    match_obj: Match,
+    // necessary to return the number as an actual number and not a string
+    #[serde(with = "bigdecimal::serde::json_num_option")]
+    average_score: Option<BigDecimal>,
+    rating_count: i64,
+}
+
+impl RatedMatch {
+    pub fn hide_result(&mut self) {
+        self.match_obj.hide_result();
+    }
+}
+
+/// Event found by a search, with its relevance and its name with the searched words highlighted
+#[derive(Queryable, Serialize, Clone)]
+pub struct EventHit {
+    #[serde(flatten)]
+    event: Event,
+    rank: f32,
+    snippet: String,
+}
+
+/// Match found by a search, the snippet comes from the text where the searched words were found
+#[derive(Queryable, Serialize, Clone)]
+pub struct MatchHit {
+    #[serde(flatten)]
+    match_obj: Match,
+    rank: f32,
+    snippet: String,
+}
+
+impl MatchHit {
+    pub fn hide_result(&mut self) {
+        self.match_obj.hide_result();
+    }
+}
+
+/// Published rating whose opinion contains the searched words
+#[derive(Queryable, Serialize, Clone)]
+pub struct RatingHit {
+    #[serde(flatten)]
+    rating: Rating,
+    rank: f32,
+    snippet: String,
+}
+
+/// Results of a search grouped by what was found, the most relevant first in each group
+#[derive(Serialize)]
+pub struct SearchResults {
+    pub events: Page<EventHit>,
+    /// Matches found by the names of their workers
+    pub matches: Page<MatchHit>,
+    /// Matches found by their description in the requested language
+    pub descriptions: Page<MatchHit>,
+    pub ratings: Page<RatingHit>,
+}
+
+/// Worker page, with its career statistics over the published ratings of its matches
+#[derive(Serialize)]
+pub struct WorkerProfile {
+    pub worker: Worker,
+    pub aliases: Vec<WorkerAlias>,
+    pub match_count: i64,
+    pub rating_count: i64,
+    #[serde(with = "bigdecimal::serde::json_num_option")]
+    pub average_score: Option<BigDecimal>,
+    pub best_match: Option<RatedMatch>,
+    pub worst_match: Option<RatedMatch>,
 }
 
 #[derive(Insertable, Queryable, Serialize, Clone)]
@@ -90,6 +636,20 @@ pub struct MatchDesc {
     language_code: Language,
 }
 
+/// Description served in the language the closest to the ones asked by the client, which is
+/// the one of `language_code`
+#[derive(Serialize)]
+pub struct NegotiatedDescription {
+    #[serde(flatten)]
+    pub description: MatchDesc,
+    /// Language asked by the client, if any was available
+    pub requested_language: Option<Language>,
+    /// Preferred tag of `Accept-Language` as sent, even when no language matches it
+    pub requested_tag: Option<String>,
+    /// Whether the description is in another language than the first one asked by the client
+    pub fallback: bool,
+}
+
 #[derive(Insertable, Queryable, Serialize, Clone)]
 #[diesel(table_name = rating)]
 #[diesel(belongs_to(Match, foreign_key = match_id))]
@@ -101,6 +661,86 @@ pub struct Rating {
     score: BigDecimal,
     publication_date: NaiveDateTime,
     opinion: Option<String>,
+    // none for the anonymous ratings made before accounts existed
+    user_id: Option<i32>,
+    // none if the rating was never edited
+    edited_at: Option<NaiveDateTime>,
+    // hidden by a moderator, only its author and the moderators can still see it
+    hidden: bool,
+}
+
+/// Version of a rating before one of its edits
+#[derive(Queryable, Serialize, Clone)]
+#[diesel(table_name = rating_revision)]
+#[diesel(belongs_to(Rating, foreign_key = rating_id))]
+pub struct RatingRevision {
+    id: i32,
+    rating_id: i32,
+    score: BigDecimal,
+    opinion: Option<String>,
+    revision_date: NaiveDateTime,
+    editor_id: Option<i32>,
+}
+
+/// Report of a rating by an account, waiting for a moderator until resolved
+#[derive(Queryable, Serialize, Clone)]
+#[diesel(table_name = rating_flag)]
+#[diesel(belongs_to(Rating, foreign_key = rating_id))]
+pub struct RatingFlag {
+    id: i32,
+    rating_id: i32,
+    user_id: i32,
+    reason: String,
+    creation_date: NaiveDateTime,
+    resolved: bool,
+}
+
+/// Rating in the moderation queue, with its unresolved flags
+#[derive(Queryable, Serialize, Clone)]
+pub struct FlaggedRating {
+    #[serde(flatten)]
+    rating: Rating,
+    flag_count: i64,
+    last_flag_date: Option<NaiveDateTime>,
+}
+
+/// Entry of the moderation audit trail
+#[derive(Queryable, Serialize, Clone)]
+#[diesel(table_name = moderation_log)]
+pub struct ModerationLog {
+    id: i32,
+    // the rating may not exist anymore
+    rating_id: i32,
+    moderator_id: Option<i32>,
+    action: ModerationAction,
+    reason: Option<String>,
+    action_date: NaiveDateTime,
+}
+
+/// Request of an account for the anonymous ratings of a username, decided by a moderator
+#[derive(Queryable, Serialize, Clone)]
+#[diesel(table_name = rating_claim)]
+pub struct RatingClaim {
+    id: i32,
+    user_id: i32,
+    username: String,
+    proof: String,
+    creation_date: NaiveDateTime,
+    /// None while pending
+    approved: Option<bool>,
+    moderator_id: Option<i32>,
+    resolution_date: Option<NaiveDateTime>,
+}
+
+#[derive(Queryable, Serialize, Clone)]
+#[diesel(table_name = users)]
+pub struct User {
+    id: i32,
+    username: String,
+    #[serde(skip_serializing)]
+    password_hash: String,
+    creation_date: NaiveDateTime,
+    role: Role,
 }
 
 impl Event {
@@ -119,6 +759,64 @@ impl Event {
     pub fn date(&self) -> &NaiveDate {
         &self.date
     }
+
+    pub fn promotion_id(&self) -> i32 {
+        self.promotion_id
+    }
+}
+
+impl Venue {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+}
+
+impl Promotion {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn abbreviation(&self) -> &Option<String> {
+        &self.abbreviation
+    }
+}
+
+impl Championship {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl TitleReign {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn worker_id(&self) -> i32 {
+        self.worker_id
+    }
+
+    pub fn start_date(&self) -> &NaiveDate {
+        &self.start_date
+    }
+
+    pub fn end_date(&self) -> &Option<NaiveDate> {
+        &self.end_date
+    }
+}
+
+impl ContestedTitle {
+    pub fn hide_result(&mut self) {
+        self.title_changed = None;
+    }
 }
 
 impl Match {
@@ -133,6 +831,75 @@ impl Match {
     pub fn workers(&self) -> &str {
         &self.workers
     }
+
+    pub fn position(&self) -> i16 {
+        self.position
+    }
+
+    /// Removes the result, for the clients that don't want spoilers
+    pub fn hide_result(&mut self) {
+        self.winning_side = None;
+        self.finish = None;
+        self.duration = None;
+    }
+}
+
+impl MatchType {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+}
+
+impl LocalizedMatchType {
+    pub fn match_type(&self) -> &MatchType {
+        &self.match_type
+    }
+}
+
+impl Worker {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl WorkerAlias {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn worker_id(&self) -> i32 {
+        self.worker_id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Participant {
+    pub fn match_id(&self) -> i32 {
+        self.match_id
+    }
+
+    pub fn side(&self) -> i16 {
+        self.side
+    }
+
+    pub fn worker_id(&self) -> i32 {
+        self.worker_id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
 }
 
 impl MatchDesc {
@@ -181,6 +948,50 @@ impl Rating {
     pub fn publication_date(&self) -> &NaiveDateTime {
         &self.publication_date
     }
+
+    pub fn user_id(&self) -> Option<i32> {
+        self.user_id
+    }
+
+    pub fn edited_at(&self) -> &Option<NaiveDateTime> {
+        &self.edited_at
+    }
+
+    pub fn hidden(&self) -> bool {
+        self.hidden
+    }
+}
+
+impl RatingClaim {
+    pub fn user_id(&self) -> i32 {
+        self.user_id
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+}
+
+impl User {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn password_hash(&self) -> &str {
+        &self.password_hash
+    }
+
+    pub fn creation_date(&self) -> &NaiveDateTime {
+        &self.creation_date
+    }
+
+    pub fn role(&self) -> Role {
+        self.role
+    }
 }
 
 // Struct without ID, this way it will be possible to
@@ -194,4 +1005,336 @@ pub struct NewRating {
     pub score: BigDecimal,
     pub publication_date: NaiveDateTime,
     pub opinion: Option<String>,
+    pub user_id: Option<i32>,
+}
+
+/// Partial update of a rating, only the given fields are changed
+#[derive(AsChangeset, Deserialize)]
+#[diesel(table_name = rating)]
+pub struct RatingChanges {
+    pub score: Option<BigDecimal>,
+    /// `null` removes the opinion
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub opinion: Option<Option<String>>,
+}
+
+/// Tells a missing field (`None`) from a field set to `null` (`Some(None)`) in a partial update
+fn deserialize_nullable<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+impl RatingChanges {
+    pub fn is_empty(&self) -> bool {
+        self.score.is_none() && self.opinion.is_none()
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = rating_revision)]
+pub struct NewRatingRevision {
+    pub rating_id: i32,
+    pub score: BigDecimal,
+    pub opinion: Option<String>,
+    pub revision_date: NaiveDateTime,
+    pub editor_id: Option<i32>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = rating_flag)]
+pub struct NewRatingFlag {
+    pub rating_id: i32,
+    pub user_id: i32,
+    pub reason: String,
+    pub creation_date: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = moderation_log)]
+pub struct NewModerationLog {
+    pub rating_id: i32,
+    pub moderator_id: Option<i32>,
+    pub action: ModerationAction,
+    pub reason: Option<String>,
+    pub action_date: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = rating_claim)]
+pub struct NewRatingClaim {
+    pub user_id: i32,
+    pub username: String,
+    pub proof: String,
+    pub creation_date: NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset, Deserialize)]
+#[diesel(table_name = event)]
+// every field is replaced on update, even the missing ones
+#[diesel(treat_none_as_null = true)]
+pub struct NewEvent {
+    pub name: String,
+    pub promotion_id: i32,
+    pub date: NaiveDate,
+    #[serde(default)]
+    pub venue_id: Option<i32>,
+    #[serde(default)]
+    pub attendance: Option<i32>,
+    #[serde(default)]
+    pub series_id: Option<i32>,
+}
+
+#[derive(Insertable, AsChangeset, Deserialize)]
+#[diesel(table_name = event_series)]
+// every field is replaced on update, even the missing ones
+#[diesel(treat_none_as_null = true)]
+pub struct NewEventSeries {
+    pub name: String,
+    pub promotion_id: Option<i32>,
+}
+
+#[derive(Insertable, AsChangeset, Deserialize)]
+#[diesel(table_name = venue)]
+// every field is replaced on update, even the missing ones
+#[diesel(treat_none_as_null = true)]
+pub struct NewVenue {
+    pub name: String,
+    pub city: String,
+    pub country: String,
+    pub capacity: Option<i32>,
+}
+
+#[derive(Insertable, AsChangeset, Deserialize)]
+#[diesel(table_name = promotion)]
+// every field is replaced on update, even the missing ones
+#[diesel(treat_none_as_null = true)]
+pub struct NewPromotion {
+    pub name: String,
+    pub abbreviation: Option<String>,
+    pub country: Option<String>,
+    pub active_from: Option<i16>,
+    pub active_until: Option<i16>,
+}
+
+#[derive(Insertable, AsChangeset, Deserialize)]
+#[diesel(table_name = championship)]
+// every field is replaced on update, even the missing ones
+#[diesel(treat_none_as_null = true)]
+pub struct NewChampionship {
+    pub name: String,
+    pub promotion_id: Option<i32>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = title_reign)]
+pub struct NewTitleReign {
+    pub championship_id: i32,
+    pub worker_id: i32,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub won_match_id: Option<i32>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = title_match)]
+pub struct NewTitleMatch {
+    pub match_id: i32,
+    pub championship_id: i32,
+    pub title_changed: bool,
+}
+
+/// Partial update of an event, only the given fields are changed
+#[derive(AsChangeset, Deserialize)]
+#[diesel(table_name = event)]
+pub struct EventChanges {
+    pub name: Option<String>,
+    pub promotion_id: Option<i32>,
+    pub date: Option<NaiveDate>,
+    pub venue_id: Option<i32>,
+    pub attendance: Option<i32>,
+    pub series_id: Option<i32>,
+}
+
+impl EventChanges {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.promotion_id.is_none()
+            && self.date.is_none()
+            && self.venue_id.is_none()
+            && self.attendance.is_none()
+            && self.series_id.is_none()
+    }
+}
+
+/// The position of a new match is given by the database, at the end of the card
+#[derive(Insertable, AsChangeset, Deserialize)]
+#[diesel(table_name = match_)]
+pub struct NewMatch {
+    pub event_id: i32,
+    pub workers: String,
+    #[serde(default)]
+    pub segment: Segment,
+}
+
+/// Partial update of a match, only the given fields are changed
+#[derive(AsChangeset, Deserialize)]
+#[diesel(table_name = match_)]
+pub struct MatchChanges {
+    pub event_id: Option<i32>,
+    pub workers: Option<String>,
+    pub segment: Option<Segment>,
+}
+
+/// Result of a match, set by the editors once it's known. Missing fields are cleared.
+#[derive(AsChangeset, Deserialize)]
+#[diesel(table_name = match_)]
+#[diesel(treat_none_as_null = true)]
+pub struct MatchResult {
+    pub winning_side: Option<i16>,
+    pub finish: Option<Finish>,
+    pub duration: Option<i32>,
+}
+
+impl MatchChanges {
+    pub fn is_empty(&self) -> bool {
+        self.event_id.is_none() && self.workers.is_none() && self.segment.is_none()
+    }
+}
+
+#[derive(Insertable, Deserialize)]
+#[diesel(table_name = match_desc)]
+pub struct NewMatchDesc {
+    pub match_id: i32,
+    pub description: String,
+    pub language_code: Language,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = worker)]
+pub struct NewWorker {
+    pub name: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = worker_alias)]
+pub struct NewWorkerAlias {
+    pub worker_id: i32,
+    pub name: String,
+    pub used_from: Option<NaiveDate>,
+    pub used_until: Option<NaiveDate>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = match_type)]
+pub struct NewMatchType {
+    pub code: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = match_type_label)]
+pub struct NewMatchTypeLabel {
+    pub match_type_id: i32,
+    pub language_code: Language,
+    pub label: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = match_classification)]
+pub struct NewMatchClassification {
+    pub match_id: i32,
+    pub match_type_id: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = match_participant)]
+pub struct NewMatchParticipant {
+    pub match_id: i32,
+    pub worker_id: i32,
+    pub side: i16,
+}
+
+/// Label of a match type in one language, before knowing the match type it belongs to
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LocalizedLabel {
+    pub language_code: Language,
+    pub label: String,
+}
+
+/// Description of a match in one language, before knowing the match it belongs to
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LocalizedDescription {
+    pub language_code: Language,
+    pub description: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = users)]
+pub struct NewUser {
+    pub username: String,
+    pub password_hash: String,
+    pub creation_date: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = session)]
+pub struct NewSession {
+    pub user_id: i32,
+    pub token_hash: String,
+    pub creation_date: NaiveDateTime,
+    pub expiration_date: NaiveDateTime,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{Finish, Match, MatchDetails, Participant, Segment};
+
+    fn match_details() -> MatchDetails {
+        let participant = |side: i16, worker_id: i32, name: &str| Participant {
+            match_id: 1,
+            side,
+            worker_id,
+            name: name.to_string(),
+        };
+
+        MatchDetails {
+            match_obj: Match {
+                id: 1,
+                event_id: 1,
+                workers: "Will Ospreay vs. Kazuchika Okada".to_string(),
+                winning_side: Some(1),
+                finish: Some(Finish::Pinfall),
+                duration: Some(1938),
+                position: 1,
+                segment: Segment::MainEvent,
+            },
+            participants: vec![
+                participant(1, 1, "Will Ospreay"),
+                participant(2, 2, "Kazuchika Okada"),
+            ],
+        }
+    }
+
+    #[test]
+    fn spoilers_only_hide_the_result() {
+        let mut details = match_details();
+        details.hide_result();
+
+        let hidden = serde_json::to_value(&details).unwrap();
+        assert_eq!(hidden["winning_side"], json!(null));
+        assert_eq!(hidden["finish"], json!(null));
+        assert_eq!(hidden["duration"], json!(null));
+
+        // the rest of the match is still given
+        let mut shown = serde_json::to_value(match_details()).unwrap();
+        for field in ["winning_side", "finish", "duration"] {
+            shown[field] = json!(null);
+        }
+        assert_eq!(hidden, shown);
+        assert_eq!(hidden["participants"].as_array().unwrap().len(), 2);
+    }
 }
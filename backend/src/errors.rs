@@ -1,23 +1,221 @@
-use diesel::r2d2;
+use diesel::{
+    r2d2,
+    result::{DatabaseErrorInformation, DatabaseErrorKind, Error as DieselError},
+};
+use rocket::{
+    Request, catch,
+    http::Status,
+    response::{self, Responder},
+    serde::json::Json,
+};
+use serde::Serialize;
+use std::fmt;
+
+use crate::{
+    pagination::{InvalidPage, PastLastPage},
+    validation::FieldError,
+};
 
 #[derive(Debug)]
 pub enum Error {
     UsernameAlreadyUsed,
     ResourceDoesNotExists,
-    WrongRating,
+    WrongRating { fields: Vec<FieldError> },
+    InvalidData { fields: Vec<FieldError> },
+    NoRatingYet,
+    AlreadyExists,
+    StillReferenced,
+    UsernameTaken,
+    Unauthorized,
+    Forbidden,
+    InvalidCredentials,
     Diesel { e: String },
     R2D2 { e: String },
     INEEDTOLOG { e: String },
 }
 
-impl From<diesel::result::Error> for Error {
-    fn from(value: diesel::result::Error) -> Self {
-        Self::Diesel {
-            e: value.to_string(),
+/// Error body sent to the clients. `code` is stable and machine-readable, `message` is meant for
+/// humans and may change.
+#[derive(Serialize)]
+pub struct ApiError {
+    code: &'static str,
+    message: String,
+    // details about each invalid field, when the request body was rejected
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fields: Vec<FieldError>,
+}
+
+impl ApiError {
+    pub fn new(code: &'static str, message: impl Into<String>) -> Self {
+        ApiError {
+            code,
+            message: message.into(),
+            fields: Vec::new(),
+        }
+    }
+}
+
+impl Error {
+    /// A field of the request refers to a row that doesn't exist
+    pub fn unknown_reference(field: &str) -> Self {
+        Error::InvalidData {
+            fields: vec![FieldError::new(
+                field,
+                "unknown_reference",
+                format!("The {} does not refer to an existing resource.", field),
+            )],
+        }
+    }
+
+    pub fn status(&self) -> Status {
+        match self {
+            Error::UsernameAlreadyUsed => Status::Conflict,
+            Error::ResourceDoesNotExists => Status::NotFound,
+            Error::WrongRating { .. } => Status::BadRequest,
+            Error::InvalidData { .. } => Status::BadRequest,
+            Error::NoRatingYet => Status::NotFound,
+            Error::AlreadyExists => Status::Conflict,
+            Error::StillReferenced => Status::Conflict,
+            Error::UsernameTaken => Status::Conflict,
+            Error::Unauthorized => Status::Unauthorized,
+            Error::Forbidden => Status::Forbidden,
+            Error::InvalidCredentials => Status::Unauthorized,
+            Error::Diesel { .. } => Status::InternalServerError,
+            // the pool could not give a connection in time: either exhausted or database offline
+            Error::R2D2 { .. } => Status::ServiceUnavailable,
+            Error::INEEDTOLOG { .. } => Status::InternalServerError,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::UsernameAlreadyUsed => "username_already_used",
+            Error::ResourceDoesNotExists => "resource_does_not_exist",
+            Error::WrongRating { .. } => "wrong_rating",
+            Error::InvalidData { .. } => "invalid_data",
+            Error::NoRatingYet => "no_rating_yet",
+            Error::AlreadyExists => "already_exists",
+            Error::StillReferenced => "still_referenced",
+            Error::UsernameTaken => "username_taken",
+            Error::Unauthorized => "unauthorized",
+            Error::Forbidden => "forbidden",
+            Error::InvalidCredentials => "invalid_credentials",
+            Error::Diesel { .. } => "database_error",
+            Error::R2D2 { .. } => "database_unavailable",
+            Error::INEEDTOLOG { .. } => "internal_error",
+        }
+    }
+
+    pub fn message(&self) -> &'static str {
+        match self {
+            Error::UsernameAlreadyUsed => {
+                "A rating has already been submitted for this match with this username."
+            }
+            Error::ResourceDoesNotExists => "The requested resource does not exist.",
+            Error::WrongRating { .. } => "This rating is not valid.",
+            Error::InvalidData { .. } => "The submitted data is not valid.",
+            Error::NoRatingYet => "This entry does not have any rating for now.",
+            Error::AlreadyExists => "This resource already exists.",
+            Error::StillReferenced => {
+                "This resource is still used by other resources, which must be removed first."
+            }
+            Error::UsernameTaken => "This username is already taken by another account.",
+            Error::Unauthorized => "You must be authenticated to do this.",
+            Error::Forbidden => "Your role does not allow you to do this.",
+            Error::InvalidCredentials => "Wrong username or password.",
+            Error::Diesel { .. } => "Could not get the data.",
+            Error::R2D2 { .. } => "The service is temporarily unavailable, please retry later.",
+            Error::INEEDTOLOG { .. } => "An internal error occurred.",
+        }
+    }
+}
+
+/// Used where there is no HTTP response to send, like in the command line
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.message(), self.code())?;
+        match self {
+            Error::Diesel { e } | Error::R2D2 { e } | Error::INEEDTOLOG { e } => {
+                write!(f, ": {}", e)
+            }
+            Error::WrongRating { fields } | Error::InvalidData { fields } => {
+                for field in fields {
+                    write!(f, "\n  {}", field)?;
+                }
+                Ok(())
+            }
+            _ => Ok(()),
         }
     }
 }
 
+impl<'r> Responder<'r, 'static> for Error {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        // internal details are only logged, never sent to the client
+        match &self {
+            Error::Diesel { e } | Error::R2D2 { e } | Error::INEEDTOLOG { e } => {
+                rocket::error!("{} {}: {}", request.method(), request.uri(), e)
+            }
+            _ => (),
+        }
+
+        let status = self.status();
+        let mut body = ApiError::new(self.code(), self.message());
+        if let Error::WrongRating { fields } | Error::InvalidData { fields } = self {
+            body.fields = fields;
+        }
+
+        (status, Json(body)).respond_to(request)
+    }
+}
+
+impl From<DieselError> for Error {
+    fn from(value: DieselError) -> Self {
+        match value {
+            DieselError::NotFound => Self::ResourceDoesNotExists,
+            DieselError::QueryBuilderError(err) if err.is::<InvalidPage>() => Self::InvalidData {
+                fields: vec![FieldError::new(
+                    "page",
+                    "out_of_range",
+                    "The page must be a number from 1.",
+                )],
+            },
+            DieselError::QueryBuilderError(err) if err.is::<PastLastPage>() => Self::InvalidData {
+                fields: vec![FieldError::new(
+                    "page",
+                    "past_last_page",
+                    "There is no item on this page, the last page is before it.",
+                )],
+            },
+            DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                Self::AlreadyExists
+            }
+            DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, info) => {
+                foreign_key_error(info.as_ref())
+            }
+            _ => Self::Diesel {
+                e: value.to_string(),
+            },
+        }
+    }
+}
+
+/// A row can't be deleted while other rows refer to it, and a row can't be written with a
+/// reference to a row that doesn't exist. In the latter case, Postgres names the column in the
+/// details, like `Key (language_code)=(XYZ) is not present in table "language".`
+fn foreign_key_error(info: &(dyn DatabaseErrorInformation + Send + Sync)) -> Error {
+    if info.message().starts_with("update or delete on table") {
+        return Error::StillReferenced;
+    }
+
+    let field = info
+        .details()
+        .and_then(|details| details.strip_prefix("Key ("))
+        .and_then(|details| details.split_once(")="))
+        .map_or("unknown", |(field, _)| field);
+    Error::unknown_reference(field)
+}
+
 impl From<r2d2::PoolError> for Error {
     fn from(value: r2d2::PoolError) -> Self {
         Self::R2D2 {
@@ -25,3 +223,105 @@ impl From<r2d2::PoolError> for Error {
         }
     }
 }
+
+/// Catchers giving the same error body as `Error` for failures happening before reaching a
+/// handler (unknown route, malformed query parameter or JSON body, ...)
+#[catch(400)]
+pub fn bad_request() -> Json<ApiError> {
+    Json(ApiError::new("bad_request", "The request is malformed."))
+}
+
+#[catch(401)]
+pub fn unauthorized() -> Json<ApiError> {
+    Json(ApiError::new(
+        "unauthorized",
+        "You must be authenticated to do this.",
+    ))
+}
+
+#[catch(403)]
+pub fn forbidden() -> Json<ApiError> {
+    Json(ApiError::new(
+        "forbidden",
+        "Your role does not allow you to do this.",
+    ))
+}
+
+#[catch(404)]
+pub fn not_found() -> Json<ApiError> {
+    Json(ApiError::new(
+        "resource_does_not_exist",
+        "The requested resource does not exist.",
+    ))
+}
+
+#[catch(422)]
+pub fn unprocessable_entity() -> Json<ApiError> {
+    Json(ApiError::new(
+        "unprocessable_entity",
+        "The request parameters or body could not be understood.",
+    ))
+}
+
+#[catch(500)]
+pub fn internal_error() -> Json<ApiError> {
+    Json(ApiError::new(
+        "internal_error",
+        "An internal error occurred.",
+    ))
+}
+
+#[catch(503)]
+pub fn service_unavailable() -> Json<ApiError> {
+    Json(ApiError::new(
+        "database_unavailable",
+        "The service is temporarily unavailable, please retry later.",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use diesel::result::Error as DieselError;
+    use rocket::http::Status;
+
+    use super::Error;
+    use crate::pagination::{InvalidPage, PastLastPage};
+
+    /// Status, field and code of the error given for a page refused by the pagination
+    fn page_error(err: DieselError) -> (Status, String, String) {
+        let error = Error::from(err);
+        let Error::InvalidData { fields } = &error else {
+            panic!("not an invalid data error: {:?}", error);
+        };
+        let field = serde_json::to_value(&fields[0]).unwrap();
+        (
+            error.status(),
+            field["field"].as_str().unwrap().to_string(),
+            field["code"].as_str().unwrap().to_string(),
+        )
+    }
+
+    #[test]
+    fn invalid_pages_are_bad_requests() {
+        assert_eq!(
+            page_error(DieselError::QueryBuilderError(Box::new(InvalidPage))),
+            (
+                Status::BadRequest,
+                "page".to_string(),
+                "out_of_range".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn pages_past_the_last_one_are_bad_requests() {
+        assert_eq!(
+            page_error(DieselError::QueryBuilderError(Box::new(PastLastPage))),
+            (
+                Status::BadRequest,
+                "page".to_string(),
+                "past_last_page".to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use chrono::NaiveDate;
+use rocket::http::ContentType;
+
+use crate::{
+    errors::Error,
+    import::{CsvRow, ImportCard},
+    validation::FieldError,
+};
+
+/// Start of the last line of an export interrupted by an error, which the import refuses
+pub const INTERRUPTION_MARK: &str = "# export interrupted:";
+
+/// Restricts the exported events, every field is optional
+#[derive(Default)]
+pub struct ExportFilter {
+    pub promotion: Option<String>,
+    /// First date included
+    pub from: Option<NaiveDate>,
+    /// Last date included
+    pub to: Option<NaiveDate>,
+}
+
+impl ExportFilter {
+    /// Builds the filter from raw parameters, dates being written as `YYYY-MM-DD`
+    pub fn new(
+        promotion: Option<String>,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Result<Self, Error> {
+        let mut fields = Vec::new();
+        let mut parse_date = |name: &'static str, value: Option<&str>| match value {
+            Some(value) => match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+                Ok(date) => Some(date),
+                Err(_) => {
+                    fields.push(FieldError::new(
+                        name,
+                        "wrong_format",
+                        "Dates must be written as YYYY-MM-DD.",
+                    ));
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let filter = ExportFilter {
+            promotion,
+            from: parse_date("from", from),
+            to: parse_date("to", to),
+        };
+
+        if fields.is_empty() {
+            Ok(filter)
+        } else {
+            Err(Error::InvalidData { fields })
+        }
+    }
+}
+
+/// Both formats can be imported back
+#[derive(Clone, Copy)]
+pub enum ExportFormat {
+    JsonLines,
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn from_name(name: &str) -> Result<Self, Error> {
+        match name.to_ascii_lowercase().as_str() {
+            "jsonl" => Ok(ExportFormat::JsonLines),
+            "csv" => Ok(ExportFormat::Csv),
+            _ => Err(Error::InvalidData {
+                fields: vec![FieldError::new(
+                    "format",
+                    "unsupported",
+                    "The export format must be jsonl or csv.",
+                )],
+            }),
+        }
+    }
+
+    pub fn content_type(&self) -> ContentType {
+        match self {
+            ExportFormat::JsonLines => ContentType::new("application", "x-ndjson"),
+            ExportFormat::Csv => ContentType::CSV,
+        }
+    }
+}
+
+/// Turns the cards into text, one card at a time, so the export never holds the whole dataset
+pub struct ExportWriter {
+    format: ExportFormat,
+    // the CSV header only comes before the first card
+    header_written: bool,
+}
+
+impl ExportWriter {
+    pub fn new(format: ExportFormat) -> Self {
+        ExportWriter {
+            format,
+            header_written: false,
+        }
+    }
+
+    pub fn write(&mut self, card: ImportCard) -> Result<String, Error> {
+        match self.format {
+            ExportFormat::JsonLines => match serde_json::to_string(&card) {
+                Ok(line) => Ok(line + "\n"),
+                Err(err) => Err(Error::INEEDTOLOG { e: err.to_string() }),
+            },
+            ExportFormat::Csv => {
+                let mut writer = csv::WriterBuilder::new()
+                    .has_headers(!self.header_written)
+                    .from_writer(Vec::new());
+
+                for row in CsvRow::from_card(card) {
+                    writer
+                        .serialize(row)
+                        .map_err(|err| Error::INEEDTOLOG { e: err.to_string() })?;
+                    self.header_written = true;
+                }
+
+                match writer.into_inner() {
+                    Ok(bytes) => String::from_utf8(bytes)
+                        .map_err(|err| Error::INEEDTOLOG { e: err.to_string() }),
+                    Err(err) => Err(Error::INEEDTOLOG { e: err.to_string() }),
+                }
+            }
+        }
+    }
+}
+
+/// Line ending an export the server couldn't finish, in both formats, so the truncated document
+/// can't be imported as if it were complete
+pub fn interruption(err: &Error) -> String {
+    format!("{} {}\n", INTERRUPTION_MARK, err.message())
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use chrono::NaiveDate;
+    use std::str::FromStr;
+
+    use super::{ExportFormat, ExportWriter, interruption};
+    use crate::{
+        entities::{Language, LocalizedDescription},
+        errors::Error,
+        import::{self, CardEvent, ImportCard, ImportFormat, ImportMatch, ImportRating},
+    };
+
+    fn card() -> ImportCard {
+        let rating = |username: &str, account: Option<&str>| ImportRating {
+            language_code: Language::ENGLISH,
+            username: username.to_string(),
+            score: BigDecimal::from_str("4.5").unwrap(),
+            publication_date: NaiveDate::from_ymd_opt(2025, 5, 26)
+                .unwrap()
+                .and_hms_opt(20, 15, 0)
+                .unwrap(),
+            opinion: Some("Match of the year, \"easily\"".to_string()),
+            account: account.map(str::to_string),
+        };
+
+        ImportCard {
+            event: CardEvent {
+                name: "Double or Nothing 2025".to_string(),
+                promotion: "AEW".to_string(),
+                date: NaiveDate::from_ymd_opt(2025, 5, 25).unwrap(),
+            },
+            matches: vec![
+                ImportMatch {
+                    workers: "FTR vs. Daniel Garcia, Nigel McGinness".to_string(),
+                    descriptions: vec![LocalizedDescription {
+                        language_code: Language::from_str("FRE").unwrap(),
+                        description: "Match en équipe".to_string(),
+                    }],
+                    ratings: vec![rating("eva", Some("Eva")), rating("bob", None)],
+                },
+                ImportMatch {
+                    workers: "Mercedes Moné vs. Kris Statlander".to_string(),
+                    descriptions: Vec::new(),
+                    ratings: Vec::new(),
+                },
+            ],
+        }
+    }
+
+    fn round_trip(export: ExportFormat, import: ImportFormat) {
+        let mut writer = ExportWriter::new(export);
+        let document = writer.write(card()).unwrap();
+        let cards = import::parse(&document, import).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&cards).unwrap(),
+            serde_json::to_value(vec![card()]).unwrap()
+        );
+    }
+
+    #[test]
+    fn json_lines_round_trip_keeps_the_accounts() {
+        round_trip(ExportFormat::JsonLines, ImportFormat::JsonLines);
+    }
+
+    #[test]
+    fn csv_round_trip_keeps_the_accounts() {
+        round_trip(ExportFormat::Csv, ImportFormat::Csv);
+    }
+
+    #[test]
+    fn interrupted_exports_cant_be_imported() {
+        for (export, import) in [
+            (ExportFormat::JsonLines, ImportFormat::JsonLines),
+            (ExportFormat::Csv, ImportFormat::Csv),
+        ] {
+            let mut writer = ExportWriter::new(export);
+            let document = writer.write(card()).unwrap()
+                + &interruption(&Error::INEEDTOLOG {
+                    e: "connection lost".to_string(),
+                });
+            assert!(import::parse(&document, import).is_err());
+        }
+    }
+
+    #[test]
+    fn anonymous_ratings_have_no_account() {
+        let mut writer = ExportWriter::new(ExportFormat::JsonLines);
+        let document = writer.write(card()).unwrap();
+        let cards = import::parse(&document, ImportFormat::JsonLines).unwrap();
+
+        let accounts: Vec<Option<&str>> = cards[0].matches[0]
+            .ratings
+            .iter()
+            .map(|rating| rating.account.as_deref())
+            .collect();
+        assert_eq!(accounts, vec![Some("Eva"), None]);
+    }
+}
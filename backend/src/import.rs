@@ -0,0 +1,450 @@
+use bigdecimal::BigDecimal;
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::{
+    entities::{Language, LocalizedDescription},
+    errors::Error,
+    export::INTERRUPTION_MARK,
+    validation::{FieldError, MAX_EVENT_NAME_LENGTH, MAX_SCORE, MAX_USERNAME_LENGTH, MIN_SCORE},
+    workers,
+};
+
+/// Size of `Promotion.name`
+const MAX_PROMOTION_LENGTH: usize = 64;
+
+/// An event and its matches, as described in an imported or exported document
+#[derive(Serialize, Deserialize)]
+pub struct ImportCard {
+    pub event: CardEvent,
+    #[serde(default)]
+    pub matches: Vec<ImportMatch>,
+}
+
+/// The promotion of an event is given by its name or its abbreviation, and must already exist
+#[derive(Serialize, Deserialize)]
+pub struct CardEvent {
+    pub name: String,
+    pub promotion: String,
+    pub date: NaiveDate,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ImportMatch {
+    pub workers: String,
+    #[serde(default)]
+    pub descriptions: Vec<LocalizedDescription>,
+    #[serde(default)]
+    pub ratings: Vec<ImportRating>,
+}
+
+/// A rating keeps its original publication date, and is recognized by its username on the match
+#[derive(Serialize, Deserialize)]
+pub struct ImportRating {
+    pub language_code: Language,
+    pub username: String,
+    pub score: BigDecimal,
+    pub publication_date: NaiveDateTime,
+    pub opinion: Option<String>,
+    /// Username of the account owning the rating, none for the anonymous ratings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+}
+
+/// A JSON document can either hold one card or a list of cards
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonDocument {
+    One(ImportCard),
+    Many(Vec<ImportCard>),
+}
+
+/// One line of a CSV document. There is one line per match description or rating, the lines
+/// sharing the same event name, promotion and match number describe the same match. A match
+/// without any description nor rating has a single line with only the match columns.
+#[derive(Serialize, Deserialize)]
+pub struct CsvRow {
+    event_name: String,
+    event_promotion: String,
+    event_date: NaiveDate,
+    match_number: usize,
+    workers: String,
+    language_code: Option<Language>,
+    description: Option<String>,
+    // the rating columns can be left out of documents without ratings
+    #[serde(default)]
+    rating_language_code: Option<Language>,
+    #[serde(default)]
+    rating_username: Option<String>,
+    #[serde(default)]
+    rating_score: Option<BigDecimal>,
+    #[serde(default)]
+    rating_publication_date: Option<NaiveDateTime>,
+    #[serde(default)]
+    rating_opinion: Option<String>,
+    #[serde(default)]
+    rating_account: Option<String>,
+}
+
+impl CsvRow {
+    /// Lines describing a card, the inverse of what `parse_csv` does
+    pub fn from_card(card: ImportCard) -> Vec<CsvRow> {
+        let mut rows = Vec::new();
+        let event = card.event;
+
+        for (i, match_obj) in card.matches.into_iter().enumerate() {
+            let row = || CsvRow {
+                event_name: event.name.clone(),
+                event_promotion: event.promotion.clone(),
+                event_date: event.date,
+                match_number: i + 1,
+                workers: match_obj.workers.clone(),
+                language_code: None,
+                description: None,
+                rating_language_code: None,
+                rating_username: None,
+                rating_score: None,
+                rating_publication_date: None,
+                rating_opinion: None,
+                rating_account: None,
+            };
+
+            if match_obj.descriptions.is_empty() && match_obj.ratings.is_empty() {
+                rows.push(row());
+            }
+
+            for description in &match_obj.descriptions {
+                rows.push(CsvRow {
+                    language_code: Some(description.language_code),
+                    description: Some(description.description.clone()),
+                    ..row()
+                });
+            }
+
+            for rating in &match_obj.ratings {
+                rows.push(CsvRow {
+                    rating_language_code: Some(rating.language_code),
+                    rating_username: Some(rating.username.clone()),
+                    rating_score: Some(rating.score.clone()),
+                    rating_publication_date: Some(rating.publication_date),
+                    rating_opinion: rating.opinion.clone(),
+                    rating_account: rating.account.clone(),
+                    ..row()
+                });
+            }
+        }
+
+        rows
+    }
+}
+
+/// What was (or would have been, for a dry run) done for an imported event
+#[derive(Serialize, Default)]
+pub struct ImportedEvent {
+    /// Not given for the events created by a dry run, as they are rolled back
+    pub id: Option<i32>,
+    pub name: String,
+    pub promotion: String,
+    pub created: bool,
+    pub matches_created: usize,
+    pub matches_existing: usize,
+    pub descriptions_created: usize,
+    pub descriptions_updated: usize,
+    pub ratings_created: usize,
+    pub ratings_existing: usize,
+    /// Ratings created without their author, whose account doesn't exist in this database
+    pub ratings_without_account: usize,
+}
+
+#[derive(Serialize)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub events: Vec<ImportedEvent>,
+}
+
+pub enum ImportFormat {
+    Json,
+    /// One card per line, as produced by the export
+    JsonLines,
+    Csv,
+}
+
+impl ImportFormat {
+    /// Guesses the format from a file name or a media subtype
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "json" => Some(ImportFormat::Json),
+            "jsonl" | "ndjson" | "x-ndjson" => Some(ImportFormat::JsonLines),
+            "csv" => Some(ImportFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+fn document_error(message: impl Into<String>) -> Error {
+    Error::InvalidData {
+        fields: vec![FieldError::new("document", "unreadable", message)],
+    }
+}
+
+/// Reads the cards from a document, and checks them before anything touches the database
+pub fn parse(document: &str, format: ImportFormat) -> Result<Vec<ImportCard>, Error> {
+    if let Some(line) = document
+        .lines()
+        .find(|line| line.starts_with(INTERRUPTION_MARK))
+    {
+        return Err(document_error(format!(
+            "The export was interrupted, the document is incomplete: {}",
+            line[INTERRUPTION_MARK.len()..].trim()
+        )));
+    }
+
+    let cards = match format {
+        ImportFormat::Json => parse_json(document)?,
+        ImportFormat::JsonLines => parse_json_lines(document)?,
+        ImportFormat::Csv => parse_csv(document)?,
+    };
+
+    validate(&cards)?;
+    Ok(cards)
+}
+
+fn parse_json(document: &str) -> Result<Vec<ImportCard>, Error> {
+    match serde_json::from_str::<JsonDocument>(document) {
+        Ok(JsonDocument::One(card)) => Ok(vec![card]),
+        Ok(JsonDocument::Many(cards)) => Ok(cards),
+        Err(err) => Err(document_error(err.to_string())),
+    }
+}
+
+fn parse_json_lines(document: &str) -> Result<Vec<ImportCard>, Error> {
+    document
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str::<ImportCard>(line)
+                .map_err(|err| document_error(format!("line {}: {}", i + 1, err)))
+        })
+        .collect()
+}
+
+fn parse_csv(document: &str) -> Result<Vec<ImportCard>, Error> {
+    let mut cards: Vec<ImportCard> = Vec::new();
+    // match number of each match, for every card
+    let mut match_numbers: Vec<Vec<usize>> = Vec::new();
+
+    for row in csv::Reader::from_reader(document.as_bytes()).deserialize::<CsvRow>() {
+        let row = row.map_err(|err| document_error(err.to_string()))?;
+
+        let card_index = match cards.iter().position(|card| {
+            card.event.name == row.event_name && card.event.promotion == row.event_promotion
+        }) {
+            Some(index) => index,
+            None => {
+                cards.push(ImportCard {
+                    event: CardEvent {
+                        name: row.event_name,
+                        promotion: row.event_promotion,
+                        date: row.event_date,
+                    },
+                    matches: Vec::new(),
+                });
+                match_numbers.push(Vec::new());
+                cards.len() - 1
+            }
+        };
+
+        let card = &mut cards[card_index];
+        let numbers = &mut match_numbers[card_index];
+        let match_index = match numbers.iter().position(|n| *n == row.match_number) {
+            Some(index) => index,
+            None => {
+                card.matches.push(ImportMatch {
+                    workers: row.workers,
+                    descriptions: Vec::new(),
+                    ratings: Vec::new(),
+                });
+                numbers.push(row.match_number);
+                card.matches.len() - 1
+            }
+        };
+
+        match (row.language_code, row.description) {
+            (Some(language_code), Some(description)) => card.matches[match_index]
+                .descriptions
+                .push(LocalizedDescription {
+                    language_code,
+                    description,
+                }),
+            (None, None) => (),
+            _ => {
+                return Err(document_error(format!(
+                    "Match {} of {} has a description without language, or the other way around.",
+                    row.match_number, card.event.name
+                )));
+            }
+        }
+
+        match (
+            row.rating_language_code,
+            row.rating_username,
+            row.rating_score,
+            row.rating_publication_date,
+        ) {
+            (Some(language_code), Some(username), Some(score), Some(publication_date)) => {
+                card.matches[match_index].ratings.push(ImportRating {
+                    language_code,
+                    username,
+                    score,
+                    publication_date,
+                    opinion: row.rating_opinion,
+                    account: row.rating_account,
+                })
+            }
+            (None, None, None, None)
+                if row.rating_opinion.is_none() && row.rating_account.is_none() => {}
+            _ => {
+                return Err(document_error(format!(
+                    "Match {} of {} has a rating without language, username, score or publication date.",
+                    row.match_number, card.event.name
+                )));
+            }
+        }
+    }
+
+    Ok(cards)
+}
+
+/// Checks the cards against the constraints of the `Event`, `Match` and `MatchDesc` tables, and
+/// reports every problem at once
+fn validate(cards: &[ImportCard]) -> Result<(), Error> {
+    let mut fields = Vec::new();
+    let mut events = HashSet::new();
+
+    if cards.is_empty() {
+        fields.push(FieldError::new(
+            "document",
+            "empty",
+            "The document does not describe any event.",
+        ));
+    }
+
+    for (i, card) in cards.iter().enumerate() {
+        let event = &card.event;
+
+        let name_length = event.name.trim().chars().count();
+        if name_length == 0 || name_length > MAX_EVENT_NAME_LENGTH {
+            fields.push(FieldError::new(
+                format!("cards[{}].event.name", i),
+                "wrong_length",
+                format!(
+                    "The event name must be between 1 and {} characters long.",
+                    MAX_EVENT_NAME_LENGTH
+                ),
+            ));
+        }
+
+        let promotion_length = event.promotion.trim().chars().count();
+        if promotion_length == 0 || promotion_length > MAX_PROMOTION_LENGTH {
+            fields.push(FieldError::new(
+                format!("cards[{}].event.promotion", i),
+                "wrong_length",
+                format!(
+                    "The promotion must be between 1 and {} characters long.",
+                    MAX_PROMOTION_LENGTH
+                ),
+            ));
+        }
+
+        if !events.insert((&event.name, &event.promotion)) {
+            fields.push(FieldError::new(
+                format!("cards[{}].event", i),
+                "duplicated",
+                "This event is already described earlier in the document.",
+            ));
+        }
+
+        // existing matches are recognized by their workers when importing again
+        let mut workers = HashSet::new();
+        for (j, match_obj) in card.matches.iter().enumerate() {
+            if match_obj.workers.trim().is_empty() {
+                fields.push(FieldError::new(
+                    format!("cards[{}].matches[{}].workers", i, j),
+                    "empty",
+                    "The workers of a match can't be empty.",
+                ));
+            } else if !workers.insert(&match_obj.workers) {
+                fields.push(FieldError::new(
+                    format!("cards[{}].matches[{}].workers", i, j),
+                    "duplicated",
+                    "A match with the same workers is already on this card.",
+                ));
+            }
+            workers::check_workers(
+                &match_obj.workers,
+                format!("cards[{}].matches[{}].workers", i, j),
+                &mut fields,
+            );
+
+            let mut languages = Vec::new();
+            for (k, description) in match_obj.descriptions.iter().enumerate() {
+                if description.description.trim().is_empty() {
+                    fields.push(FieldError::new(
+                        format!("cards[{}].matches[{}].descriptions[{}]", i, j, k),
+                        "empty",
+                        "A description can't be empty.",
+                    ));
+                }
+                if languages.contains(&description.language_code) {
+                    fields.push(FieldError::new(
+                        format!("cards[{}].matches[{}].descriptions[{}]", i, j, k),
+                        "duplicated",
+                        "This match already has a description in this language.",
+                    ));
+                }
+                languages.push(description.language_code);
+            }
+
+            let mut usernames = HashSet::new();
+            for (k, rating) in match_obj.ratings.iter().enumerate() {
+                let field = format!("cards[{}].matches[{}].ratings[{}]", i, j, k);
+
+                if rating.score < BigDecimal::from(MIN_SCORE)
+                    || rating.score > BigDecimal::from(MAX_SCORE)
+                {
+                    fields.push(FieldError::new(
+                        format!("{}.score", field),
+                        "out_of_range",
+                        format!("The score must be between {} and {}.", MIN_SCORE, MAX_SCORE),
+                    ));
+                }
+
+                let username_length = rating.username.trim().chars().count();
+                if username_length == 0 || username_length > MAX_USERNAME_LENGTH {
+                    fields.push(FieldError::new(
+                        format!("{}.username", field),
+                        "wrong_length",
+                        format!(
+                            "The username must be between 1 and {} characters long.",
+                            MAX_USERNAME_LENGTH
+                        ),
+                    ));
+                } else if !usernames.insert(&rating.username) {
+                    fields.push(FieldError::new(
+                        format!("{}.username", field),
+                        "duplicated",
+                        "This username already rated this match.",
+                    ));
+                }
+            }
+        }
+    }
+
+    if fields.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::InvalidData { fields })
+    }
+}
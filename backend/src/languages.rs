@@ -0,0 +1,230 @@
+use dotenvy::dotenv;
+use rocket::{
+    Request,
+    request::{FromRequest, Outcome},
+};
+use std::{cmp::Ordering, env, str::FromStr};
+
+use crate::{
+    database::DatabaseOperations,
+    entities::{Language, LanguageInfo},
+    errors::Error,
+    validation,
+};
+
+/// Languages tried, in order, when the client did not ask for one that is available
+pub struct LanguageFallback {
+    pub chain: Vec<Language>,
+    /// Whether any available language may be served once the chain is exhausted
+    pub any: bool,
+}
+
+impl Default for LanguageFallback {
+    fn default() -> Self {
+        LanguageFallback {
+            chain: vec![Language::ENGLISH],
+            any: true,
+        }
+    }
+}
+
+impl LanguageFallback {
+    /// Reads `LANGUAGE_FALLBACK`, a comma separated list of language codes where `*` stands for
+    /// any language, like `FRE,ENG,*`
+    pub fn from_env() -> Self {
+        dotenv().ok();
+
+        let Ok(fallback) = env::var("LANGUAGE_FALLBACK") else {
+            return LanguageFallback::default();
+        };
+
+        let mut chain = Vec::new();
+        let mut any = false;
+        for code in fallback
+            .split(',')
+            .map(str::trim)
+            .filter(|code| !code.is_empty())
+        {
+            if code == "*" {
+                any = true;
+            } else {
+                chain.push(Language::from_str(code).unwrap_or_else(|_| {
+                    panic!("LANGUAGE_FALLBACK must only contain language codes and '*'")
+                }));
+            }
+        }
+
+        LanguageFallback { chain, any }
+    }
+}
+
+/// Request guard reading the `Accept-Language` header. The tags are kept as sent, from the most
+/// to the least preferred, once each and without the refused ones. The malformed items are
+/// skipped, and so is `*` as the fallback chain decides which languages come after the named
+/// ones.
+#[derive(Debug, PartialEq)]
+pub struct AcceptedLanguages(pub Vec<String>);
+
+impl AcceptedLanguages {
+    fn parse(header: &str) -> Self {
+        let mut tags = header
+            .split(',')
+            .filter_map(|item| {
+                let mut parts = item.split(';').map(str::trim);
+                let tag = parts.next()?;
+                let quality = match parts.find_map(|part| part.strip_prefix("q=")) {
+                    Some(quality) => quality.parse::<f32>().ok()?,
+                    None => 1.0,
+                };
+                let valid = !tag.is_empty() && tag != "*" && (0.0..=1.0).contains(&quality);
+                (valid && quality > 0.0).then_some((tag, quality))
+            })
+            .collect::<Vec<_>>();
+        // the sort is stable, so the tags with the same quality keep the order of the header
+        tags.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+
+        let mut accepted: Vec<String> = Vec::new();
+        for (tag, _) in tags {
+            if !accepted.iter().any(|other| other.eq_ignore_ascii_case(tag)) {
+                accepted.push(tag.to_string());
+            }
+        }
+        AcceptedLanguages(accepted)
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AcceptedLanguages {
+    type Error = Error;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let header = request
+            .headers()
+            .get("Accept-Language")
+            .collect::<Vec<_>>()
+            .join(",");
+        Outcome::Success(AcceptedLanguages::parse(&header))
+    }
+}
+
+/// Languages acceptable for a client, from the most to the least preferred
+pub struct LanguagePreferences {
+    /// Language asked with `lang` or, without it, the first available one of `Accept-Language`
+    pub requested: Option<Language>,
+    /// Preferred tag of `Accept-Language`, even when no language matches it
+    pub first_tag: Option<String>,
+    pub languages: Vec<Language>,
+    /// Language asked with `lang` or, without it, the one of the preferred tag if available
+    first_choice: Option<Language>,
+}
+
+impl LanguagePreferences {
+    /// Whether a language is served instead of the first one asked by the client. Nothing is a
+    /// fallback when the client didn't ask for any language.
+    pub fn is_fallback(&self, language: Language) -> bool {
+        let asked = self.first_choice.is_some() || self.first_tag.is_some();
+        asked && self.first_choice != Some(language)
+    }
+}
+
+fn matches_tag(language: &LanguageInfo, tag: &str) -> bool {
+    let primary = tag.split('-').next().unwrap_or(tag);
+    language
+        .short_code
+        .as_deref()
+        .is_some_and(|short_code| short_code.trim().eq_ignore_ascii_case(primary))
+        || language.code.as_str().eq_ignore_ascii_case(primary)
+}
+
+/// Orders the available languages: the one given with `lang`, then the ones of `Accept-Language`,
+/// then the fallback chain and finally, when allowed, every other language.
+pub fn preferences(
+    lang: Option<Language>,
+    accepted: &AcceptedLanguages,
+    fallback: &LanguageFallback,
+    database: &impl DatabaseOperations,
+) -> Result<LanguagePreferences, Error> {
+    if let Some(lang) = lang {
+        validation::validate_language(lang, "lang", database)?;
+    }
+
+    let available = database.get_languages()?;
+    let language_of = |tag: &String| {
+        available
+            .iter()
+            .find(|language| matches_tag(language, tag))
+            .map(|language| language.code)
+    };
+    let first_tag = accepted.0.first().cloned();
+    let first_choice = lang.or_else(|| first_tag.as_ref().and_then(language_of));
+    let accepted = accepted.0.iter().filter_map(language_of);
+    let requested = lang.or_else(|| accepted.clone().next());
+
+    let chain = fallback
+        .chain
+        .iter()
+        .copied()
+        .filter(|code| available.iter().any(|language| language.code == *code));
+    let any = available
+        .iter()
+        .map(|language| language.code)
+        .filter(|_| fallback.any);
+
+    let mut languages = Vec::new();
+    for language in lang.into_iter().chain(accepted).chain(chain).chain(any) {
+        if !languages.contains(&language) {
+            languages.push(language);
+        }
+    }
+
+    Ok(LanguagePreferences {
+        requested,
+        first_tag,
+        languages,
+        first_choice,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AcceptedLanguages;
+
+    fn parse(header: &str) -> Vec<String> {
+        AcceptedLanguages::parse(header).0
+    }
+
+    #[test]
+    fn sorts_the_tags_by_quality() {
+        assert_eq!(
+            parse("en;q=0.5, fr-CH, de;q=0.8, fr;q=0.9"),
+            vec!["fr-CH", "fr", "de", "en"]
+        );
+        // same quality, same order as the header
+        assert_eq!(parse("es;q=0.7, en;q=0.7"), vec!["es", "en"]);
+    }
+
+    #[test]
+    fn leaves_out_the_refused_tags() {
+        assert_eq!(parse("fr;q=0, en, de;q=0.0"), vec!["en"]);
+    }
+
+    #[test]
+    fn keeps_each_tag_once() {
+        assert_eq!(parse("en;q=0.2, fr, EN, en;q=0.9"), vec!["fr", "EN"]);
+    }
+
+    #[test]
+    fn skips_the_malformed_items() {
+        assert_eq!(
+            parse("fr;q=high, ;q=0.5, , en;q=1.5, de;q=-1, es ;q=0.3"),
+            vec!["es"]
+        );
+        assert!(parse("").is_empty());
+    }
+
+    #[test]
+    fn leaves_the_wildcard_to_the_fallback_chain() {
+        assert_eq!(parse("*"), Vec::<String>::new());
+        assert_eq!(parse("*;q=0.9, fr;q=0.5"), vec!["fr"]);
+    }
+}
@@ -1,31 +1,76 @@
-use rocket::{launch, routes};
+use rocket::{Build, Rocket, catchers, routes};
 use rocket_cors::CorsOptions;
+use std::{env, process};
 
 use crate::endpoints::{
-    add_match_rating, get_average_rating_for_event, get_average_rating_for_match, get_event,
-    get_event_ratings, get_match, get_match_desc, get_ratings,
+    add_championship, add_event, add_language, add_match, add_match_desc, add_match_rating,
+    add_match_type, add_promotion, add_series, add_title_reign, add_venue, add_worker_alias,
+    delete_event, delete_match, delete_match_desc, delete_rating, delete_title_match,
+    delete_title_reign, delete_worker_alias, export_cards, flag_rating,
+    get_average_rating_for_event, get_average_rating_for_match, get_average_rating_for_match_type,
+    get_average_rating_for_promotion, get_best_venues, get_championship, get_championships,
+    get_contested_titles, get_event, get_event_ratings, get_languages, get_match, get_match_desc,
+    get_match_types, get_match_types_for_match, get_moderation_log, get_moderation_queue,
+    get_one_series, get_promotion, get_promotion_events, get_promotions, get_rating,
+    get_rating_flags, get_rating_revisions, get_ratings, get_ratings_feed, get_series,
+    get_series_editions, get_title_history, get_venue, get_venue_events, get_venues, get_worker,
+    get_worker_aliases, get_worker_matches, import_cards, merge_promotions, moderate_rating,
+    patch_event, patch_match, patch_rating, reorder_card, search, set_match_result,
+    set_match_types, set_title_match, update_championship, update_event, update_match,
+    update_match_desc, update_promotion, update_series, update_venue,
 };
 use crate::{
     database::{Database, ManageDatabaseConnection},
-    endpoints::{get_event_matches, get_events, get_match_ratings},
+    endpoints::{
+        claim_ratings, get_event_matches, get_events, get_match_ratings, get_me, get_my_ratings,
+        get_rating_claims, get_users, login, logout, register, resolve_rating_claim, set_user_role,
+    },
+    errors::{
+        bad_request, forbidden, internal_error, not_found, service_unavailable, unauthorized,
+        unprocessable_entity,
+    },
+    languages::LanguageFallback,
+    validation::RatingRules,
 };
 
+pub mod auth;
+pub mod classification;
+pub mod cli;
 pub mod database;
 pub mod endpoints;
 pub mod entities;
 pub mod errors;
+pub mod export;
+pub mod import;
+pub mod languages;
 pub mod pagination;
 pub mod schema;
+pub mod validation;
+pub mod workers;
 
 pub struct AppState {
     database: Database,
+    rating_rules: RatingRules,
+    language_fallback: LanguageFallback,
 }
 
-#[launch]
-fn rocket() -> _ {
+fn main() {
+    // any argument is a command to run instead of the server
+    let args: Vec<String> = env::args().skip(1).collect();
+    if !args.is_empty() {
+        process::exit(cli::run(&args));
+    }
+
+    // the launch error, if any, is reported when dropped
+    let _ = rocket::execute(rocket().launch());
+}
+
+fn rocket() -> Rocket<Build> {
     // put the database in the appstate
     let state = AppState {
         database: Database::new(),
+        rating_rules: RatingRules::from_env(),
+        language_fallback: LanguageFallback::from_env(),
     };
 
     // allow every origins, necessary to do brower-initiated requests
@@ -43,9 +88,98 @@ fn rocket() -> _ {
                 get_match_desc,
                 get_match_ratings,
                 get_ratings,
+                get_ratings_feed,
+                search,
                 add_match_rating,
+                patch_rating,
+                delete_rating,
+                get_rating_revisions,
+                flag_rating,
+                get_rating,
+                get_rating_flags,
+                get_moderation_queue,
+                moderate_rating,
+                get_moderation_log,
                 get_average_rating_for_event,
                 get_average_rating_for_match,
+                add_event,
+                update_event,
+                patch_event,
+                reorder_card,
+                delete_event,
+                add_match,
+                update_match,
+                patch_match,
+                set_match_result,
+                get_match_types_for_match,
+                set_match_types,
+                get_contested_titles,
+                set_title_match,
+                delete_title_match,
+                delete_match,
+                add_match_desc,
+                update_match_desc,
+                delete_match_desc,
+                get_worker,
+                get_worker_matches,
+                get_worker_aliases,
+                add_worker_alias,
+                delete_worker_alias,
+                get_promotions,
+                get_promotion,
+                get_promotion_events,
+                get_average_rating_for_promotion,
+                add_promotion,
+                update_promotion,
+                merge_promotions,
+                get_series,
+                get_one_series,
+                get_series_editions,
+                add_series,
+                update_series,
+                get_venues,
+                get_best_venues,
+                get_venue,
+                get_venue_events,
+                add_venue,
+                update_venue,
+                get_languages,
+                add_language,
+                get_match_types,
+                get_average_rating_for_match_type,
+                add_match_type,
+                get_championships,
+                get_championship,
+                get_title_history,
+                add_championship,
+                update_championship,
+                add_title_reign,
+                delete_title_reign,
+                import_cards,
+                export_cards,
+                register,
+                login,
+                logout,
+                get_me,
+                get_my_ratings,
+                claim_ratings,
+                get_rating_claims,
+                resolve_rating_claim,
+                get_users,
+                set_user_role,
+            ],
+        )
+        // every error, even the ones raised by Rocket itself, has the same body
+        .register(
+            "/",
+            catchers![
+                bad_request,
+                unauthorized,
+                forbidden,
+                not_found,
+                unprocessable_entity,
+                internal_error,
+                service_unavailable
             ],
         )
         // inject the appstate in the server for requests to use it
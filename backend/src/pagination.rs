@@ -1,17 +1,52 @@
 // Code taken from https://github.com/diesel-rs/diesel/blob/main/examples/postgres/advanced-blog-cli/src/pagination.rs
 
+use chrono::{DateTime, NaiveDateTime};
 use diesel::pg::Pg;
 use diesel::prelude::*;
 use diesel::query_builder::*;
 use diesel::query_dsl::methods::LoadQuery;
-use diesel::sql_types::BigInt;
+use diesel::result::Error as DieselError;
+use diesel::sql_types::{BigInt, Nullable};
+use rocket::FromFormField;
 use serde::Serialize;
+use std::{error, fmt, str::FromStr};
 
 pub trait Paginate: Sized {
     fn paginate(self, page: i64) -> Paginated<Self>;
 }
 
-const DEFAULT_PER_PAGE: i64 = 10;
+pub const DEFAULT_PER_PAGE: i64 = 10;
+/// Bigger pages can be asked by the clients, up to this size
+pub const MAX_PER_PAGE: i64 = 100;
+
+/// Direction of the sorting of a list
+#[derive(FromFormField, Clone, Copy, Debug, PartialEq)]
+pub enum Order {
+    #[field(value = "asc")]
+    Asc,
+    #[field(value = "desc")]
+    Desc,
+}
+
+/// What the events can be sorted by
+#[derive(FromFormField, Clone, Copy, Debug, PartialEq)]
+pub enum EventSort {
+    #[field(value = "date")]
+    Date,
+    /// Average score of the published ratings of their matches, the events without any come last
+    #[field(value = "average")]
+    Average,
+}
+
+/// What the ratings can be sorted by
+#[derive(FromFormField, Clone, Copy, Debug, PartialEq)]
+pub enum RatingSort {
+    /// Publication date
+    #[field(value = "date")]
+    Date,
+    #[field(value = "score")]
+    Score,
+}
 
 impl<T> Paginate for T {
     fn paginate(self, page: i64) -> Paginated<Self> {
@@ -19,17 +54,145 @@ impl<T> Paginate for T {
             query: self,
             per_page: DEFAULT_PER_PAGE,
             page,
-            offset: (page - 1) * DEFAULT_PER_PAGE,
+            offset: offset(page, DEFAULT_PER_PAGE),
         }
     }
 }
 
+/// Position of the first item of a page, none for the pages that can't exist
+fn offset(page: i64, per_page: i64) -> Option<i64> {
+    if page < 1 {
+        return None;
+    }
+    (page - 1).checked_mul(per_page)
+}
+
+/// Page number below 1 or too big to be computed, the query is not sent to the database
+#[derive(Debug)]
+pub struct InvalidPage;
+
+impl fmt::Display for InvalidPage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid page number")
+    }
+}
+
+impl error::Error for InvalidPage {}
+
+/// Page after the last one, whose rows can't tell how many items there are
+#[derive(Debug)]
+pub struct PastLastPage;
+
+impl fmt::Display for PastLastPage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "page past the last one")
+    }
+}
+
+impl error::Error for PastLastPage {}
+
 // intended for user display
 #[derive(Debug, Clone, Serialize)]
 pub struct Page<T> {
     pub page: i64,
+    pub per_page: i64,
     pub page_total: i64,
+    pub total_items: i64,
+    pub has_next: bool,
+    pub has_prev: bool,
+    pub items: Vec<T>,
+}
+
+impl<T> Page<T> {
+    /// Page of a list of `total_items` items, split in pages of `per_page` items
+    pub fn new(page: i64, per_page: i64, total_items: i64, items: Vec<T>) -> Self {
+        let page_total = (total_items as f64 / per_page as f64).ceil() as i64;
+        Page {
+            page,
+            per_page,
+            page_total,
+            total_items,
+            has_next: page < page_total,
+            has_prev: page > 1,
+            items,
+        }
+    }
+
+    /// Same page with other items, for the items completed after being loaded
+    pub fn with_items<U>(self, items: Vec<U>) -> Page<U> {
+        Page {
+            page: self.page,
+            per_page: self.per_page,
+            page_total: self.page_total,
+            total_items: self.total_items,
+            has_next: self.has_next,
+            has_prev: self.has_prev,
+            items,
+        }
+    }
+}
+
+/// Position in a feed sorted by publication date, from the most recent item to the oldest. The
+/// items published at the same time are sorted by id.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cursor {
+    /// The items published before the given date and id, the following page
+    After(NaiveDateTime, i32),
+    /// The items published after the given date and id, the previous page
+    Before(NaiveDateTime, i32),
+}
+
+/// Cursors are opaque for the clients, who only send back the ones they were given
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (direction, date, id) = match self {
+            Cursor::After(date, id) => ('a', date, id),
+            Cursor::Before(date, id) => ('b', date, id),
+        };
+        write!(
+            f,
+            "{}{:016x}{:08x}",
+            direction,
+            date.and_utc().timestamp_micros() as u64,
+            *id as u32
+        )
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = ();
+
+    fn from_str(cursor: &str) -> Result<Self, Self::Err> {
+        // from_str_radix also accepts a sign, which the server never writes
+        if cursor.len() != 25
+            || !cursor.is_ascii()
+            || !cursor[1..].bytes().all(|byte| byte.is_ascii_hexdigit())
+        {
+            return Err(());
+        }
+        let micros = u64::from_str_radix(&cursor[1..17], 16).map_err(|_| ())? as i64;
+        let date = DateTime::from_timestamp_micros(micros)
+            .ok_or(())?
+            .naive_utc();
+        let id = u32::from_str_radix(&cursor[17..], 16).map_err(|_| ())? as i32;
+        match &cursor[..1] {
+            "a" => Ok(Cursor::After(date, id)),
+            "b" => Ok(Cursor::Before(date, id)),
+            _ => Err(()),
+        }
+    }
+}
+
+// intended for user display, the feeds are not counted unless the client asks for it
+#[derive(Debug, Clone, Serialize)]
+pub struct CursorPage<T> {
     pub items: Vec<T>,
+    /// None on the last page
+    pub next_cursor: Option<String>,
+    /// None on the first page
+    pub prev_cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_items: Option<i64>,
 }
 
 #[derive(Debug, Clone, Copy, QueryId)]
@@ -37,14 +200,14 @@ pub struct Paginated<T> {
     query: T,
     page: i64,
     per_page: i64,
-    offset: i64,
+    offset: Option<i64>,
 }
 
 impl<T> Paginated<T> {
     pub fn per_page(self, per_page: i64) -> Self {
         Paginated {
             per_page,
-            offset: (self.page - 1) * per_page,
+            offset: offset(self.page, per_page),
             ..self
         }
     }
@@ -53,17 +216,45 @@ impl<T> Paginated<T> {
     where
         Self: LoadQuery<'a, PgConnection, (U, i64)>,
     {
+        if self.offset.is_none() {
+            return Err(DieselError::QueryBuilderError(Box::new(InvalidPage)));
+        }
         let per_page = self.per_page;
         let page = self.page;
         let results = self.load::<(U, i64)>(conn)?;
+        // past the last page there is no row to count, only the first page can be empty
+        if results.is_empty() && page > 1 {
+            return Err(DieselError::QueryBuilderError(Box::new(PastLastPage)));
+        }
         let total = results.first().map(|x| x.1).unwrap_or(0);
         let items = results.into_iter().map(|x| x.0).collect();
-        let page_total = (total as f64 / per_page as f64).ceil() as i64;
-        Ok(Page {
-            page,
-            page_total,
-            items,
-        })
+        Ok(Page::new(page, per_page, total, items))
+    }
+}
+
+impl<T: Clone> Paginated<T> {
+    /// Same as `load_and_count_pages`, except that a page past the last one is empty instead of
+    /// refused, for the lists shown side by side which don't end on the same page. The items are
+    /// then counted with their first one.
+    pub fn load_or_count_pages<'a, U>(self, conn: &mut PgConnection) -> QueryResult<Page<U>>
+    where
+        Self: LoadQuery<'a, PgConnection, (U, i64)>,
+    {
+        let first_item = Paginated {
+            query: self.query.clone(),
+            page: 1,
+            per_page: 1,
+            offset: Some(0),
+        };
+        let (page, per_page) = (self.page, self.per_page);
+
+        match self.load_and_count_pages::<U>(conn) {
+            Err(DieselError::QueryBuilderError(err)) if err.is::<PastLastPage>() => {
+                let total = first_item.load_and_count_pages::<U>(conn)?.total_items;
+                Ok(Page::new(page, per_page, total, Vec::new()))
+            }
+            result => result,
+        }
     }
 }
 
@@ -83,7 +274,95 @@ where
         out.push_sql(") t LIMIT ");
         out.push_bind_param::<BigInt, _>(&self.per_page)?;
         out.push_sql(" OFFSET ");
-        out.push_bind_param::<BigInt, _>(&self.offset)?;
+        out.push_bind_param::<Nullable<BigInt>, _>(&self.offset)?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveDateTime};
+
+    use super::{Cursor, Page, offset};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_micro_opt(20, 15, 30, 123_456)
+            .unwrap()
+    }
+
+    #[test]
+    fn pages_start_at_one() {
+        assert_eq!(offset(1, 10), Some(0));
+        assert_eq!(offset(3, 10), Some(20));
+        assert_eq!(offset(0, 10), None);
+        assert_eq!(offset(-1, 10), None);
+    }
+
+    #[test]
+    fn refuses_the_pages_too_far_to_be_computed() {
+        assert_eq!(offset(i64::MAX, 100), None);
+        assert_eq!(offset(i64::MAX / 100 + 1, 100), Some(i64::MAX / 100 * 100));
+    }
+
+    #[test]
+    fn pages_know_their_neighbours() {
+        let page = Page::new(2, 10, 25, vec![(); 10]);
+        assert_eq!(page.page_total, 3);
+        assert!(page.has_prev);
+        assert!(page.has_next);
+
+        let last = Page::new(3, 10, 25, vec![(); 5]);
+        assert_eq!(last.page_total, 3);
+        assert!(!last.has_next);
+
+        let full = Page::new(1, 10, 10, vec![(); 10]);
+        assert_eq!(full.page_total, 1);
+        assert!(!full.has_prev);
+        assert!(!full.has_next);
+
+        let empty = Page::<()>::new(1, 10, 0, Vec::new());
+        assert_eq!(empty.page_total, 0);
+        assert!(!empty.has_prev);
+        assert!(!empty.has_next);
+    }
+
+    #[test]
+    fn cursors_are_read_back() {
+        for cursor in [
+            Cursor::After(date(2025, 5, 25), 42),
+            Cursor::Before(date(2025, 5, 25), i32::MAX),
+            Cursor::After(date(1969, 12, 31), -1),
+            Cursor::Before(date(1901, 1, 1), i32::MIN),
+        ] {
+            let written = cursor.to_string();
+            assert_eq!(written.len(), 25);
+            assert_eq!(written.parse::<Cursor>(), Ok(cursor));
+        }
+    }
+
+    #[test]
+    fn refuses_a_wrong_length() {
+        let cursor = Cursor::After(date(2025, 5, 25), 42).to_string();
+        assert!(cursor[..24].parse::<Cursor>().is_err());
+        assert!(format!("{}0", cursor).parse::<Cursor>().is_err());
+        assert!("".parse::<Cursor>().is_err());
+    }
+
+    #[test]
+    fn refuses_characters_other_than_hexadecimal_digits() {
+        assert!("a00000000000000zz00000001".parse::<Cursor>().is_err());
+        assert!("a+00000000000000000000001".parse::<Cursor>().is_err());
+        assert!("a0000000000000000+0000001".parse::<Cursor>().is_err());
+        // multibyte characters can't be sliced in the middle
+        assert!("aé0000000000000000000001".parse::<Cursor>().is_err());
+    }
+
+    #[test]
+    fn refuses_an_unknown_direction() {
+        assert!("c00000000000000000000002a".parse::<Cursor>().is_err());
+        assert!("A00000000000000000000002a".parse::<Cursor>().is_err());
+        assert!("a00000000000000000000002a".parse::<Cursor>().is_ok());
+    }
+}
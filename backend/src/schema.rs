@@ -1,13 +1,33 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    championship (id) {
+        id -> Int4,
+        #[max_length = 128]
+        name -> Varchar,
+        promotion_id -> Nullable<Int4>,
+    }
+}
+
 diesel::table! {
     event (id) {
         id -> Int4,
         #[max_length = 128]
         name -> Varchar,
-        #[max_length = 64]
-        promotion -> Varchar,
         date -> Date,
+        promotion_id -> Int4,
+        venue_id -> Nullable<Int4>,
+        attendance -> Nullable<Int4>,
+        series_id -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    event_series (id) {
+        id -> Int4,
+        #[max_length = 128]
+        name -> Varchar,
+        promotion_id -> Nullable<Int4>,
     }
 }
 
@@ -15,6 +35,12 @@ diesel::table! {
     language (code) {
         #[max_length = 3]
         code -> Bpchar,
+        #[max_length = 64]
+        name -> Varchar,
+        #[max_length = 64]
+        native_name -> Varchar,
+        #[max_length = 2]
+        short_code -> Nullable<Bpchar>,
     }
 }
 
@@ -24,6 +50,20 @@ diesel::table! {
         id -> Int4,
         event_id -> Int4,
         workers -> Text,
+        winning_side -> Nullable<Int2>,
+        #[max_length = 16]
+        finish -> Nullable<Varchar>,
+        duration -> Nullable<Int4>,
+        position -> Int2,
+        #[max_length = 16]
+        segment -> Varchar,
+    }
+}
+
+diesel::table! {
+    match_classification (match_id, match_type_id) {
+        match_id -> Int4,
+        match_type_id -> Int4,
     }
 }
 
@@ -37,6 +77,59 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    match_participant (id) {
+        id -> Int4,
+        match_id -> Int4,
+        worker_id -> Int4,
+        side -> Int2,
+    }
+}
+
+diesel::table! {
+    match_type (id) {
+        id -> Int4,
+        #[max_length = 32]
+        code -> Varchar,
+    }
+}
+
+diesel::table! {
+    match_type_label (match_type_id, language_code) {
+        match_type_id -> Int4,
+        #[max_length = 3]
+        language_code -> Bpchar,
+        #[max_length = 64]
+        label -> Varchar,
+    }
+}
+
+diesel::table! {
+    moderation_log (id) {
+        id -> Int4,
+        rating_id -> Int4,
+        moderator_id -> Nullable<Int4>,
+        #[max_length = 16]
+        action -> Varchar,
+        reason -> Nullable<Text>,
+        action_date -> Timestamp,
+    }
+}
+
+diesel::table! {
+    promotion (id) {
+        id -> Int4,
+        #[max_length = 64]
+        name -> Varchar,
+        #[max_length = 16]
+        abbreviation -> Nullable<Varchar>,
+        #[max_length = 64]
+        country -> Nullable<Varchar>,
+        active_from -> Nullable<Int2>,
+        active_until -> Nullable<Int2>,
+    }
+}
+
 diesel::table! {
     rating (id) {
         id -> Int4,
@@ -48,13 +141,185 @@ diesel::table! {
         score -> Numeric,
         publication_date -> Timestamp,
         opinion -> Nullable<Text>,
+        user_id -> Nullable<Int4>,
+        edited_at -> Nullable<Timestamp>,
+        hidden -> Bool,
+    }
+}
+
+diesel::table! {
+    rating_claim (id) {
+        id -> Int4,
+        user_id -> Int4,
+        #[max_length = 32]
+        username -> Varchar,
+        proof -> Text,
+        creation_date -> Timestamp,
+        approved -> Nullable<Bool>,
+        moderator_id -> Nullable<Int4>,
+        resolution_date -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    rating_flag (id) {
+        id -> Int4,
+        rating_id -> Int4,
+        user_id -> Int4,
+        reason -> Text,
+        creation_date -> Timestamp,
+        resolved -> Bool,
+    }
+}
+
+diesel::table! {
+    rating_revision (id) {
+        id -> Int4,
+        rating_id -> Int4,
+        score -> Numeric,
+        opinion -> Nullable<Text>,
+        revision_date -> Timestamp,
+        editor_id -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    role (code) {
+        #[max_length = 16]
+        code -> Varchar,
+    }
+}
+
+diesel::table! {
+    session (id) {
+        id -> Int4,
+        user_id -> Int4,
+        #[max_length = 64]
+        token_hash -> Bpchar,
+        creation_date -> Timestamp,
+        expiration_date -> Timestamp,
+    }
+}
+
+diesel::table! {
+    title_match (id) {
+        id -> Int4,
+        match_id -> Int4,
+        championship_id -> Int4,
+        title_changed -> Bool,
+    }
+}
+
+diesel::table! {
+    title_reign (id) {
+        id -> Int4,
+        championship_id -> Int4,
+        worker_id -> Int4,
+        start_date -> Date,
+        end_date -> Nullable<Date>,
+        won_match_id -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    users (id) {
+        id -> Int4,
+        #[max_length = 32]
+        username -> Varchar,
+        password_hash -> Text,
+        creation_date -> Timestamp,
+        #[max_length = 16]
+        role_code -> Varchar,
+    }
+}
+
+diesel::table! {
+    venue (id) {
+        id -> Int4,
+        #[max_length = 128]
+        name -> Varchar,
+        #[max_length = 64]
+        city -> Varchar,
+        #[max_length = 64]
+        country -> Varchar,
+        capacity -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    worker (id) {
+        id -> Int4,
+        #[max_length = 128]
+        name -> Varchar,
+    }
+}
+
+diesel::table! {
+    worker_alias (id) {
+        id -> Int4,
+        worker_id -> Int4,
+        #[max_length = 128]
+        name -> Varchar,
+        used_from -> Nullable<Date>,
+        used_until -> Nullable<Date>,
     }
 }
 
+diesel::joinable!(championship -> promotion (promotion_id));
+diesel::joinable!(event -> event_series (series_id));
+diesel::joinable!(event -> promotion (promotion_id));
+diesel::joinable!(event -> venue (venue_id));
+diesel::joinable!(event_series -> promotion (promotion_id));
 diesel::joinable!(match_ -> event (event_id));
+diesel::joinable!(match_classification -> match_ (match_id));
+diesel::joinable!(match_classification -> match_type (match_type_id));
 diesel::joinable!(match_desc -> language (language_code));
 diesel::joinable!(match_desc -> match_ (match_id));
+diesel::joinable!(match_participant -> match_ (match_id));
+diesel::joinable!(match_participant -> worker (worker_id));
+diesel::joinable!(match_type_label -> language (language_code));
+diesel::joinable!(match_type_label -> match_type (match_type_id));
+diesel::joinable!(moderation_log -> users (moderator_id));
 diesel::joinable!(rating -> language (language_code));
 diesel::joinable!(rating -> match_ (match_id));
+diesel::joinable!(rating -> users (user_id));
+diesel::joinable!(rating_claim -> users (user_id));
+diesel::joinable!(rating_flag -> rating (rating_id));
+diesel::joinable!(rating_flag -> users (user_id));
+diesel::joinable!(rating_revision -> rating (rating_id));
+diesel::joinable!(rating_revision -> users (editor_id));
+diesel::joinable!(session -> users (user_id));
+diesel::joinable!(title_match -> championship (championship_id));
+diesel::joinable!(title_match -> match_ (match_id));
+diesel::joinable!(title_reign -> championship (championship_id));
+diesel::joinable!(title_reign -> match_ (won_match_id));
+diesel::joinable!(title_reign -> worker (worker_id));
+diesel::joinable!(users -> role (role_code));
+diesel::joinable!(worker_alias -> worker (worker_id));
 
-diesel::allow_tables_to_appear_in_same_query!(event, language, match_, match_desc, rating,);
+diesel::allow_tables_to_appear_in_same_query!(
+    championship,
+    event,
+    event_series,
+    language,
+    match_,
+    match_classification,
+    match_desc,
+    match_participant,
+    match_type,
+    match_type_label,
+    moderation_log,
+    promotion,
+    rating,
+    rating_claim,
+    rating_flag,
+    rating_revision,
+    role,
+    session,
+    title_match,
+    title_reign,
+    users,
+    venue,
+    worker,
+    worker_alias,
+);
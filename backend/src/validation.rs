@@ -0,0 +1,991 @@
+use bigdecimal::{BigDecimal, Zero};
+use dotenvy::dotenv;
+use serde::Serialize;
+use std::{env, fmt, str::FromStr};
+
+use crate::{
+    database::DatabaseOperations,
+    endpoints::{
+        RequestAccount, RequestAlias, RequestClaim, RequestFlag, RequestMatchType, RequestRating,
+        RequestReign,
+    },
+    entities::{
+        EventChanges, Language, LanguageInfo, MatchChanges, MatchResult, NewChampionship, NewEvent,
+        NewEventSeries, NewMatch, NewPromotion, NewVenue, RatingChanges,
+    },
+    errors::Error,
+    pagination::{Cursor, MAX_PER_PAGE},
+    workers::{self, MAX_WORKER_NAME_LENGTH},
+};
+
+/// Bounds enforced by `CHK_Rating`
+pub const MIN_SCORE: i32 = 0;
+pub const MAX_SCORE: i32 = 5;
+/// Size of `Rating.username` and `Users.username`
+pub const MAX_USERNAME_LENGTH: usize = 32;
+const MIN_PASSWORD_LENGTH: usize = 8;
+/// Flag reasons are short explanations for the moderators, not opinions
+const MAX_FLAG_REASON_LENGTH: usize = 500;
+/// Same for the proofs given with the rating claims
+const MAX_CLAIM_PROOF_LENGTH: usize = 500;
+/// Sizes of `Promotion.name`, `Promotion.abbreviation` and `Promotion.country`
+const MAX_PROMOTION_NAME_LENGTH: usize = 64;
+const MAX_ABBREVIATION_LENGTH: usize = 16;
+const MAX_COUNTRY_LENGTH: usize = 64;
+/// Sizes of `Match_Type.code` and `Match_Type_Label.label`
+const MAX_MATCH_TYPE_CODE_LENGTH: usize = 32;
+const MAX_MATCH_TYPE_LABEL_LENGTH: usize = 64;
+/// Size of `Event.name`
+pub const MAX_EVENT_NAME_LENGTH: usize = 128;
+/// Sizes of `Venue.name`, `Venue.city` and `Venue.country`
+const MAX_VENUE_NAME_LENGTH: usize = 128;
+const MAX_CITY_LENGTH: usize = 64;
+/// Size of `Event_Series.name`
+const MAX_SERIES_NAME_LENGTH: usize = 128;
+/// Size of `Championship.name`
+const MAX_CHAMPIONSHIP_NAME_LENGTH: usize = 128;
+/// Sizes of `Language.name` and `Language.native_name`
+const MAX_LANGUAGE_NAME_LENGTH: usize = 64;
+/// Searches are a few words, not whole texts
+const MAX_SEARCH_LENGTH: usize = 128;
+/// Hashing very long passwords is slow, they are refused to avoid wasting time on them
+const MAX_PASSWORD_LENGTH: usize = 128;
+
+/// One invalid field of a request, sent back to the client along with the others
+#[derive(Serialize, Debug)]
+pub struct FieldError {
+    field: String,
+    code: &'static str,
+    message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, code: &'static str, message: impl Into<String>) -> Self {
+        FieldError {
+            field: field.into(),
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} ({})", self.field, self.message, self.code)
+    }
+}
+
+/// Rules applied to the ratings sent by the clients.
+pub struct RatingRules {
+    /// Scores must be a multiple of this step. The score column only keeps one decimal, so
+    /// the step should be a multiple of 0.1.
+    pub score_step: BigDecimal,
+    /// Maximum number of characters in an opinion
+    pub max_opinion_length: usize,
+}
+
+impl Default for RatingRules {
+    fn default() -> Self {
+        RatingRules {
+            score_step: BigDecimal::new(5.into(), 1),
+            max_opinion_length: 2000,
+        }
+    }
+}
+
+impl RatingRules {
+    /// Reads `RATING_SCORE_STEP` and `RATING_OPINION_MAX_LENGTH`, falling back to the defaults
+    pub fn from_env() -> Self {
+        dotenv().ok();
+
+        let mut rules = RatingRules::default();
+
+        if let Ok(step) = env::var("RATING_SCORE_STEP") {
+            rules.score_step = match BigDecimal::from_str(&step) {
+                Ok(step) if step > BigDecimal::zero() => step,
+                _ => panic!("RATING_SCORE_STEP must be a positive decimal number"),
+            };
+        }
+
+        if let Ok(length) = env::var("RATING_OPINION_MAX_LENGTH") {
+            rules.max_opinion_length = length
+                .parse()
+                .expect("RATING_OPINION_MAX_LENGTH must be a positive integer");
+        }
+
+        rules
+    }
+}
+
+fn is_allowed_in_username(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ' ')
+}
+
+fn check_username(username: &str, fields: &mut Vec<FieldError>) {
+    let username_length = username.chars().count();
+    if username_length == 0 || username_length > MAX_USERNAME_LENGTH {
+        fields.push(FieldError::new(
+            "username",
+            "wrong_length",
+            format!(
+                "The username must be between 1 and {} characters long.",
+                MAX_USERNAME_LENGTH
+            ),
+        ));
+    } else if !username.chars().all(is_allowed_in_username) {
+        fields.push(FieldError::new(
+            "username",
+            "forbidden_characters",
+            "The username may only contain letters, digits, spaces, '_', '-' and '.'.",
+        ));
+    }
+}
+
+fn check_score(score: &BigDecimal, rules: &RatingRules, fields: &mut Vec<FieldError>) {
+    if *score < BigDecimal::from(MIN_SCORE) || *score > BigDecimal::from(MAX_SCORE) {
+        fields.push(FieldError::new(
+            "score",
+            "out_of_range",
+            format!("The score must be between {} and {}.", MIN_SCORE, MAX_SCORE),
+        ));
+    } else if !(score % &rules.score_step).is_zero() {
+        fields.push(FieldError::new(
+            "score",
+            "wrong_step",
+            format!("The score must be a multiple of {}.", rules.score_step),
+        ));
+    }
+}
+
+fn check_attendance(attendance: Option<i32>, fields: &mut Vec<FieldError>) {
+    if let Some(attendance) = attendance
+        && attendance < 0
+    {
+        fields.push(FieldError::new(
+            "attendance",
+            "negative",
+            "The attendance can't be negative.",
+        ));
+    }
+}
+
+fn check_event_name(name: &str, fields: &mut Vec<FieldError>) {
+    let length = name.trim().chars().count();
+    if length == 0 || length > MAX_EVENT_NAME_LENGTH {
+        fields.push(FieldError::new(
+            "name",
+            "wrong_length",
+            format!(
+                "The name must be between 1 and {} characters long.",
+                MAX_EVENT_NAME_LENGTH
+            ),
+        ));
+    }
+}
+
+/// A reference checked before saving, so it's reported along with the other fields
+fn check_reference<T>(
+    field: &str,
+    found: Result<T, Error>,
+    fields: &mut Vec<FieldError>,
+) -> Result<(), Error> {
+    match found {
+        Ok(_) => Ok(()),
+        Err(Error::ResourceDoesNotExists) => {
+            fields.push(FieldError::new(
+                field,
+                "unknown_reference",
+                format!("The {} does not refer to an existing resource.", field),
+            ));
+            Ok(())
+        }
+        Err(err) => Err(err),
+    }
+}
+
+fn check_match_workers(workers: &str, fields: &mut Vec<FieldError>) {
+    if workers.trim().is_empty() {
+        fields.push(FieldError::new(
+            "workers",
+            "empty",
+            "The workers of a match can't be empty.",
+        ));
+    }
+    workers::check_workers(workers, "workers", fields);
+}
+
+/// The languages are not fixed, they are checked in the `language` table
+fn check_language(
+    language: Language,
+    field: impl Into<String>,
+    database: &impl DatabaseOperations,
+    fields: &mut Vec<FieldError>,
+) -> Result<(), Error> {
+    match database.get_language_by(language) {
+        Ok(_) => Ok(()),
+        Err(Error::ResourceDoesNotExists) => {
+            fields.push(FieldError::new(
+                field,
+                "unknown_language",
+                format!("The language {} is not available.", language),
+            ));
+            Ok(())
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Checks a language given alone, like the one of the query of a list
+pub fn validate_language(
+    language: Language,
+    field: &str,
+    database: &impl DatabaseOperations,
+) -> Result<(), Error> {
+    let mut fields = Vec::new();
+    check_language(language, field, database, &mut fields)?;
+
+    if fields.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::InvalidData { fields })
+    }
+}
+
+fn check_opinion(opinion: &str, rules: &RatingRules, fields: &mut Vec<FieldError>) {
+    if opinion.chars().count() > rules.max_opinion_length {
+        fields.push(FieldError::new(
+            "opinion",
+            "too_long",
+            format!(
+                "The opinion must not exceed {} characters.",
+                rules.max_opinion_length
+            ),
+        ));
+    }
+}
+
+impl RequestAccount {
+    /// Checks the username and password of a new account
+    pub fn validate(&self) -> Result<(), Error> {
+        let mut fields = Vec::new();
+
+        // spaces around the username are removed before creating the account
+        check_username(self.username.trim(), &mut fields);
+
+        let password_length = self.password.chars().count();
+        if !(MIN_PASSWORD_LENGTH..=MAX_PASSWORD_LENGTH).contains(&password_length) {
+            fields.push(FieldError::new(
+                "password",
+                "wrong_length",
+                format!(
+                    "The password must be between {} and {} characters long.",
+                    MIN_PASSWORD_LENGTH, MAX_PASSWORD_LENGTH
+                ),
+            ));
+        }
+
+        if fields.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidData { fields })
+        }
+    }
+}
+
+impl RequestRating {
+    /// Checks every field of the rating, and returns all the problems at once in
+    /// `Error::WrongRating` so the client can show them together.
+    pub fn validate(
+        &self,
+        rules: &RatingRules,
+        database: &impl DatabaseOperations,
+    ) -> Result<(), Error> {
+        let mut fields = Vec::new();
+
+        check_score(&self.score, rules, &mut fields);
+        if let Some(opinion) = &self.opinion {
+            check_opinion(opinion, rules, &mut fields);
+        }
+
+        match database.get_match_by(self.match_id) {
+            Ok(_) => (),
+            Err(Error::ResourceDoesNotExists) => fields.push(FieldError::new(
+                "match_id",
+                "unknown_match",
+                "This match does not exist.",
+            )),
+            Err(err) => return Err(err),
+        }
+        check_language(self.language_code, "language_code", database, &mut fields)?;
+
+        if fields.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::WrongRating { fields })
+        }
+    }
+}
+
+impl RatingChanges {
+    /// Same checks as a new rating, on the fields being changed
+    pub fn validate(&self, rules: &RatingRules) -> Result<(), Error> {
+        let mut fields = Vec::new();
+
+        if let Some(score) = &self.score {
+            check_score(score, rules, &mut fields);
+        }
+        if let Some(Some(opinion)) = &self.opinion {
+            check_opinion(opinion, rules, &mut fields);
+        }
+
+        if fields.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::WrongRating { fields })
+        }
+    }
+}
+
+impl RequestClaim {
+    pub fn validate(&self) -> Result<(), Error> {
+        let mut fields = Vec::new();
+
+        check_username(self.username.trim(), &mut fields);
+        let proof_length = self.proof.trim().chars().count();
+        if proof_length == 0 || proof_length > MAX_CLAIM_PROOF_LENGTH {
+            fields.push(FieldError::new(
+                "proof",
+                "wrong_length",
+                format!(
+                    "The proof must be between 1 and {} characters long.",
+                    MAX_CLAIM_PROOF_LENGTH
+                ),
+            ));
+        }
+
+        if fields.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidData { fields })
+        }
+    }
+}
+
+impl RequestFlag {
+    pub fn validate(&self) -> Result<(), Error> {
+        let reason_length = self.reason.trim().chars().count();
+        if reason_length == 0 || reason_length > MAX_FLAG_REASON_LENGTH {
+            return Err(Error::InvalidData {
+                fields: vec![FieldError::new(
+                    "reason",
+                    "wrong_length",
+                    format!(
+                        "The reason must be between 1 and {} characters long.",
+                        MAX_FLAG_REASON_LENGTH
+                    ),
+                )],
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl RequestAlias {
+    pub fn validate(&self) -> Result<(), Error> {
+        let mut fields = Vec::new();
+
+        let name_length = self.name.trim().chars().count();
+        if name_length == 0 || name_length > MAX_WORKER_NAME_LENGTH {
+            fields.push(FieldError::new(
+                "name",
+                "wrong_length",
+                format!(
+                    "The name must be between 1 and {} characters long.",
+                    MAX_WORKER_NAME_LENGTH
+                ),
+            ));
+        }
+
+        if let (Some(used_from), Some(used_until)) = (self.used_from, self.used_until)
+            && used_from > used_until
+        {
+            fields.push(FieldError::new(
+                "used_until",
+                "before_start",
+                "A name can't stop being used before it started.",
+            ));
+        }
+
+        if fields.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidData { fields })
+        }
+    }
+}
+
+impl NewPromotion {
+    pub fn validate(&self) -> Result<(), Error> {
+        let mut fields = Vec::new();
+
+        let name_length = self.name.trim().chars().count();
+        if name_length == 0 || name_length > MAX_PROMOTION_NAME_LENGTH {
+            fields.push(FieldError::new(
+                "name",
+                "wrong_length",
+                format!(
+                    "The name must be between 1 and {} characters long.",
+                    MAX_PROMOTION_NAME_LENGTH
+                ),
+            ));
+        }
+
+        if let Some(abbreviation) = &self.abbreviation
+            && abbreviation.chars().count() > MAX_ABBREVIATION_LENGTH
+        {
+            fields.push(FieldError::new(
+                "abbreviation",
+                "wrong_length",
+                format!(
+                    "The abbreviation must not exceed {} characters.",
+                    MAX_ABBREVIATION_LENGTH
+                ),
+            ));
+        }
+
+        if let Some(country) = &self.country
+            && country.chars().count() > MAX_COUNTRY_LENGTH
+        {
+            fields.push(FieldError::new(
+                "country",
+                "wrong_length",
+                format!(
+                    "The country must not exceed {} characters.",
+                    MAX_COUNTRY_LENGTH
+                ),
+            ));
+        }
+
+        if let (Some(active_from), Some(active_until)) = (self.active_from, self.active_until)
+            && active_from > active_until
+        {
+            fields.push(FieldError::new(
+                "active_until",
+                "before_start",
+                "A promotion can't close before it opened.",
+            ));
+        }
+
+        if fields.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidData { fields })
+        }
+    }
+}
+
+impl MatchResult {
+    /// Checks the result against the workers of the match, the winning side must be one of them
+    pub fn validate(&self, workers: &str) -> Result<(), Error> {
+        let mut fields = Vec::new();
+
+        if let Some(winning_side) = self.winning_side {
+            let side_count = workers::parse_sides(workers).len();
+            if winning_side < 1 || winning_side as usize > side_count {
+                fields.push(FieldError::new(
+                    "winning_side",
+                    "unknown_side",
+                    format!("The winning side must be between 1 and {}.", side_count),
+                ));
+            } else if let Some(finish) = self.finish
+                && !finish.has_winner()
+            {
+                fields.push(FieldError::new(
+                    "winning_side",
+                    "no_decision",
+                    "A match ending this way has no winner.",
+                ));
+            }
+        }
+
+        if let Some(duration) = self.duration
+            && duration < 0
+        {
+            fields.push(FieldError::new(
+                "duration",
+                "negative",
+                "The duration can't be negative.",
+            ));
+        }
+
+        if fields.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidData { fields })
+        }
+    }
+}
+
+impl NewChampionship {
+    pub fn validate(&self) -> Result<(), Error> {
+        let name_length = self.name.trim().chars().count();
+        if name_length == 0 || name_length > MAX_CHAMPIONSHIP_NAME_LENGTH {
+            return Err(Error::InvalidData {
+                fields: vec![FieldError::new(
+                    "name",
+                    "wrong_length",
+                    format!(
+                        "The name must be between 1 and {} characters long.",
+                        MAX_CHAMPIONSHIP_NAME_LENGTH
+                    ),
+                )],
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl RequestReign {
+    pub fn validate(&self) -> Result<(), Error> {
+        if let Some(end_date) = self.end_date
+            && end_date < self.start_date
+        {
+            return Err(Error::InvalidData {
+                fields: vec![FieldError::new(
+                    "end_date",
+                    "before_start",
+                    "A reign can't end before it started.",
+                )],
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl RequestMatchType {
+    pub fn validate(&self, database: &impl DatabaseOperations) -> Result<(), Error> {
+        let mut fields = Vec::new();
+
+        // the code is given in the query of the filtered lists, it stays simple to write
+        let code_length = self.code.chars().count();
+        if code_length == 0
+            || code_length > MAX_MATCH_TYPE_CODE_LENGTH
+            || !self
+                .code
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        {
+            fields.push(FieldError::new(
+                "code",
+                "wrong_format",
+                format!(
+                    "The code must be between 1 and {} lowercase letters, digits or underscores.",
+                    MAX_MATCH_TYPE_CODE_LENGTH
+                ),
+            ));
+        }
+
+        for (i, label) in self.labels.iter().enumerate() {
+            let label_length = label.label.trim().chars().count();
+            if label_length == 0 || label_length > MAX_MATCH_TYPE_LABEL_LENGTH {
+                fields.push(FieldError::new(
+                    format!("labels[{}].label", i),
+                    "wrong_length",
+                    format!(
+                        "The label must be between 1 and {} characters long.",
+                        MAX_MATCH_TYPE_LABEL_LENGTH
+                    ),
+                ));
+            }
+            if self.labels[..i]
+                .iter()
+                .any(|other| other.language_code == label.language_code)
+            {
+                fields.push(FieldError::new(
+                    format!("labels[{}].language_code", i),
+                    "duplicate",
+                    "A match type has only one label per language.",
+                ));
+            }
+            check_language(
+                label.language_code,
+                format!("labels[{}].language_code", i),
+                database,
+                &mut fields,
+            )?;
+        }
+
+        if fields.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidData { fields })
+        }
+    }
+}
+
+impl LanguageInfo {
+    pub fn validate(&self) -> Result<(), Error> {
+        let mut fields = Vec::new();
+
+        for (field, value) in [("name", &self.name), ("native_name", &self.native_name)] {
+            let length = value.trim().chars().count();
+            if length == 0 || length > MAX_LANGUAGE_NAME_LENGTH {
+                fields.push(FieldError::new(
+                    field,
+                    "wrong_length",
+                    format!(
+                        "The name must be between 1 and {} characters long.",
+                        MAX_LANGUAGE_NAME_LENGTH
+                    ),
+                ));
+            }
+        }
+
+        if let Some(short_code) = &self.short_code {
+            let short_code = short_code.trim();
+            if short_code.len() != 2 || !short_code.chars().all(|c| c.is_ascii_alphabetic()) {
+                fields.push(FieldError::new(
+                    "short_code",
+                    "invalid_format",
+                    "The short code must be made of 2 letters, like \"fr\".",
+                ));
+            }
+        }
+
+        if fields.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidData { fields })
+        }
+    }
+}
+
+/// Checks the text of a search, which must contain something to search
+pub fn validate_search(query: &str) -> Result<(), Error> {
+    let length = query.trim().chars().count();
+    if length == 0 || length > MAX_SEARCH_LENGTH {
+        Err(Error::InvalidData {
+            fields: vec![FieldError::new(
+                "q",
+                "wrong_length",
+                format!(
+                    "The search must be between 1 and {} characters long.",
+                    MAX_SEARCH_LENGTH
+                ),
+            )],
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks the page size asked by a client, if it asked for one
+pub fn validate_per_page(per_page: Option<i64>) -> Result<(), Error> {
+    match per_page {
+        Some(per_page) if !(1..=MAX_PER_PAGE).contains(&per_page) => Err(Error::InvalidData {
+            fields: vec![FieldError::new(
+                "per_page",
+                "out_of_range",
+                format!("The page size must be between 1 and {}.", MAX_PER_PAGE),
+            )],
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Reads a cursor given by the client, which must be one of the cursors sent by the server
+pub fn parse_cursor(cursor: &str) -> Result<Cursor, Error> {
+    cursor.parse().map_err(|_| Error::InvalidData {
+        fields: vec![FieldError::new(
+            "cursor",
+            "invalid",
+            "The cursor is not valid, start again from the first page.",
+        )],
+    })
+}
+
+impl NewEvent {
+    pub fn validate(&self, database: &impl DatabaseOperations) -> Result<(), Error> {
+        let mut fields = Vec::new();
+        check_event_name(&self.name, &mut fields);
+        check_reference(
+            "promotion_id",
+            database.get_promotion_by(self.promotion_id),
+            &mut fields,
+        )?;
+        check_attendance(self.attendance, &mut fields);
+
+        if fields.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidData { fields })
+        }
+    }
+}
+
+impl EventChanges {
+    pub fn validate(&self, database: &impl DatabaseOperations) -> Result<(), Error> {
+        let mut fields = Vec::new();
+        if let Some(name) = &self.name {
+            check_event_name(name, &mut fields);
+        }
+        if let Some(promotion_id) = self.promotion_id {
+            check_reference(
+                "promotion_id",
+                database.get_promotion_by(promotion_id),
+                &mut fields,
+            )?;
+        }
+        check_attendance(self.attendance, &mut fields);
+
+        if fields.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidData { fields })
+        }
+    }
+}
+
+impl NewMatch {
+    pub fn validate(&self, database: &impl DatabaseOperations) -> Result<(), Error> {
+        let mut fields = Vec::new();
+        check_reference(
+            "event_id",
+            database.get_event_by(self.event_id),
+            &mut fields,
+        )?;
+        check_match_workers(&self.workers, &mut fields);
+
+        if fields.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidData { fields })
+        }
+    }
+}
+
+impl MatchChanges {
+    pub fn validate(&self, database: &impl DatabaseOperations) -> Result<(), Error> {
+        let mut fields = Vec::new();
+        if let Some(event_id) = self.event_id {
+            check_reference("event_id", database.get_event_by(event_id), &mut fields)?;
+        }
+        if let Some(workers) = &self.workers {
+            check_match_workers(workers, &mut fields);
+        }
+
+        if fields.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidData { fields })
+        }
+    }
+}
+
+impl NewVenue {
+    pub fn validate(&self) -> Result<(), Error> {
+        let mut fields = Vec::new();
+
+        for (field, value, max_length) in [
+            ("name", &self.name, MAX_VENUE_NAME_LENGTH),
+            ("city", &self.city, MAX_CITY_LENGTH),
+            ("country", &self.country, MAX_COUNTRY_LENGTH),
+        ] {
+            let length = value.trim().chars().count();
+            if length == 0 || length > max_length {
+                fields.push(FieldError::new(
+                    field,
+                    "wrong_length",
+                    format!(
+                        "The {} must be between 1 and {} characters long.",
+                        field, max_length
+                    ),
+                ));
+            }
+        }
+
+        if let Some(capacity) = self.capacity
+            && capacity <= 0
+        {
+            fields.push(FieldError::new(
+                "capacity",
+                "not_positive",
+                "The capacity must be positive.",
+            ));
+        }
+
+        if fields.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidData { fields })
+        }
+    }
+}
+
+impl NewEventSeries {
+    pub fn validate(&self) -> Result<(), Error> {
+        let name_length = self.name.trim().chars().count();
+        if name_length == 0 || name_length > MAX_SERIES_NAME_LENGTH {
+            return Err(Error::InvalidData {
+                fields: vec![FieldError::new(
+                    "name",
+                    "wrong_length",
+                    format!(
+                        "The name must be between 1 and {} characters long.",
+                        MAX_SERIES_NAME_LENGTH
+                    ),
+                )],
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use dotenvy::dotenv;
+    use std::{env, str::FromStr};
+
+    use super::{FieldError, RatingRules, check_opinion, check_score, validate_per_page};
+    use crate::{
+        database::{Database, ManageDatabaseConnection},
+        endpoints::RequestRating,
+        entities::{Language, RatingChanges},
+        errors::Error,
+        pagination::MAX_PER_PAGE,
+    };
+
+    fn score_codes(score: &str, rules: &RatingRules) -> Vec<&'static str> {
+        let mut fields = Vec::new();
+        check_score(&BigDecimal::from_str(score).unwrap(), rules, &mut fields);
+        fields.iter().map(|field| field.code).collect()
+    }
+
+    /// Field and code of every problem found in a rating
+    fn rating_problems(result: Result<(), Error>) -> Vec<(String, &'static str)> {
+        match result {
+            Err(Error::WrongRating { fields }) => fields
+                .into_iter()
+                .map(|FieldError { field, code, .. }| (field, code))
+                .collect(),
+            Err(err) => panic!("not a rating error: {:?}", err),
+            Ok(()) => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn scores_stay_between_the_bounds() {
+        let rules = RatingRules::default();
+        assert!(score_codes("0", &rules).is_empty());
+        assert!(score_codes("5", &rules).is_empty());
+        assert_eq!(score_codes("-0.5", &rules), vec!["out_of_range"]);
+        assert_eq!(score_codes("5.5", &rules), vec!["out_of_range"]);
+    }
+
+    #[test]
+    fn scores_follow_the_step() {
+        let rules = RatingRules::default();
+        assert!(score_codes("4.5", &rules).is_empty());
+        assert_eq!(score_codes("4.3", &rules), vec!["wrong_step"]);
+
+        let rules = RatingRules {
+            score_step: BigDecimal::from_str("0.1").unwrap(),
+            ..RatingRules::default()
+        };
+        assert!(score_codes("4.3", &rules).is_empty());
+    }
+
+    #[test]
+    fn opinions_have_a_maximum_length() {
+        let rules = RatingRules {
+            max_opinion_length: 10,
+            ..RatingRules::default()
+        };
+
+        let mut fields = Vec::new();
+        // characters are counted, not bytes
+        check_opinion("éééééééééé", &rules, &mut fields);
+        assert!(fields.is_empty());
+        check_opinion("ééééééééééé", &rules, &mut fields);
+        assert_eq!(fields[0].code, "too_long");
+    }
+
+    #[test]
+    fn changes_report_every_problem_at_once() {
+        let rules = RatingRules {
+            max_opinion_length: 10,
+            ..RatingRules::default()
+        };
+
+        let changes = RatingChanges {
+            score: Some(BigDecimal::from(7)),
+            opinion: Some(Some("far too long opinion".to_string())),
+        };
+        assert_eq!(
+            rating_problems(changes.validate(&rules)),
+            vec![
+                ("score".to_string(), "out_of_range"),
+                ("opinion".to_string(), "too_long")
+            ]
+        );
+
+        // removing the opinion is always allowed
+        let changes = RatingChanges {
+            score: None,
+            opinion: Some(None),
+        };
+        assert!(rating_problems(changes.validate(&rules)).is_empty());
+    }
+
+    #[test]
+    fn page_sizes_have_a_maximum() {
+        assert!(validate_per_page(None).is_ok());
+        assert!(validate_per_page(Some(1)).is_ok());
+        assert!(validate_per_page(Some(MAX_PER_PAGE)).is_ok());
+
+        for per_page in [0, -1, MAX_PER_PAGE + 1] {
+            match validate_per_page(Some(per_page)) {
+                Err(Error::InvalidData { fields }) => {
+                    assert_eq!(fields[0].field, "per_page");
+                    assert_eq!(fields[0].code, "out_of_range");
+                }
+                _ => panic!("page size {} accepted", per_page),
+            }
+        }
+    }
+
+    #[test]
+    fn ratings_report_every_problem_at_once() {
+        // the match and the language are looked up in the database
+        dotenv().ok();
+        if env::var("DATABASE_URL").is_err() {
+            return;
+        }
+        let database = Database::new();
+        let rules = RatingRules {
+            max_opinion_length: 10,
+            ..RatingRules::default()
+        };
+
+        let rating = RequestRating {
+            match_id: -1,
+            language_code: Language::from_str("ZZZ").unwrap(),
+            score: BigDecimal::from_str("4.3").unwrap(),
+            opinion: Some("far too long opinion".to_string()),
+        };
+        assert_eq!(
+            rating_problems(rating.validate(&rules, &database)),
+            vec![
+                ("score".to_string(), "wrong_step"),
+                ("opinion".to_string(), "too_long"),
+                ("match_id".to_string(), "unknown_match"),
+                ("language_code".to_string(), "unknown_language"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use crate::validation::FieldError;
+
+/// Size of `Worker.name` and `Worker_Alias.name`
+pub const MAX_WORKER_NAME_LENGTH: usize = 128;
+
+/// Splits a workers string like "FTR vs. Daniel Garcia, Nigel McGinness" into its sides, and
+/// each side into its workers. Sides are separated by "vs.", workers by ",". The side of a
+/// worker is its index plus one, so an empty side keeps its number.
+///
+/// The migration creating the participants follows the same rules.
+pub fn parse_sides(workers: &str) -> Vec<Vec<String>> {
+    let mut sides = vec![Vec::new()];
+    for word in workers.split_whitespace() {
+        if word == "vs." {
+            sides.push(Vec::new());
+        } else if let Some(side) = sides.last_mut() {
+            side.push(word);
+        }
+    }
+
+    sides
+        .into_iter()
+        .map(|words| {
+            words
+                .join(" ")
+                .split(',')
+                .map(normalize_name)
+                .filter(|name| !name.is_empty())
+                .collect()
+        })
+        .collect()
+}
+
+/// Writes a name the way the workers are saved, without extra spaces
+pub fn normalize_name(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Checks that every worker of the string can be saved
+pub fn check_workers(workers: &str, field: impl Into<String>, fields: &mut Vec<FieldError>) {
+    let too_long = parse_sides(workers)
+        .iter()
+        .flatten()
+        .any(|name| name.chars().count() > MAX_WORKER_NAME_LENGTH);
+
+    if too_long {
+        fields.push(FieldError::new(
+            field,
+            "wrong_length",
+            format!(
+                "The name of a worker must not exceed {} characters.",
+                MAX_WORKER_NAME_LENGTH
+            ),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_sides;
+
+    #[test]
+    fn splits_sides_and_workers() {
+        assert_eq!(
+            parse_sides("FTR vs. Daniel Garcia, Nigel McGinness"),
+            vec![vec!["FTR"], vec!["Daniel Garcia", "Nigel McGinness"]]
+        );
+        assert_eq!(
+            parse_sides("  A ,  B   vs.   C  "),
+            vec![vec!["A", "B"], vec!["C"]]
+        );
+    }
+
+    #[test]
+    fn keeps_the_number_of_empty_sides() {
+        assert_eq!(
+            parse_sides("vs. The Young Bucks"),
+            vec![vec![], vec!["The Young Bucks"]]
+        );
+        assert_eq!(parse_sides("A vs."), vec![vec!["A"], vec![]]);
+        assert_eq!(
+            parse_sides("A vs. vs. B"),
+            vec![vec!["A"], vec![], vec!["B"]]
+        );
+    }
+
+    #[test]
+    fn only_splits_on_vs_as_a_word() {
+        assert_eq!(parse_sides("Avs. B vs.C"), vec![vec!["Avs. B vs.C"]]);
+    }
+}
@@ -1,13 +1,20 @@
 INSERT INTO Language (code) VALUES ('FRE'), ('ENG');
 
+INSERT INTO Promotion (name, abbreviation, country, active_from) VALUES
+  ('All Elite Wrestling', 'AEW', 'United States', 2019),
+  ('New Japan Pro Wrestling', 'NJPW', 'Japan', 1972),
+  ('World Wrestling Entertainment', 'WWE', 'United States', 1953),
+  ('World Wonder Ring Stardom', 'Stardom', 'Japan', 2010),
+  ('Association les Professionnels du Catch, Banger Zone Wrestling', NULL, 'France', NULL);
+
 DO $$
 DECLARE 
   event_id INTEGER;
   match_id INTEGER;
 BEGIN
-  INSERT INTO Event (name, promotion, date)
+  INSERT INTO Event (name, promotion_id, date)
   VALUES 
-    ('AEW Double or Nothing 2025', 'All Elite Wrestling','2025-05-25')
+    ('AEW Double or Nothing 2025', (SELECT id FROM Promotion WHERE name = 'All Elite Wrestling'),'2025-05-25')
   RETURNING id INTO event_id;
 
   INSERT INTO Match (event_id, workers) VALUES 
@@ -88,9 +95,9 @@ DECLARE
   event_id INTEGER;
   match_id INTEGER;
 BEGIN
-  INSERT INTO Event (name, promotion, date)
+  INSERT INTO Event (name, promotion_id, date)
   VALUES 
-      ('AEW All In 2025', 'All Elite Wrestling', '2025-07-12')
+      ('AEW All In 2025', (SELECT id FROM Promotion WHERE name = 'All Elite Wrestling'), '2025-07-12')
     RETURNING id INTO event_id;
 
   INSERT INTO Match (event_id, workers) VALUES
@@ -172,9 +179,9 @@ DECLARE
   event_id INTEGER;
   match_id INTEGER;
 BEGIN
-  INSERT INTO Event (name, promotion, date)
+  INSERT INTO Event (name, promotion_id, date)
   VALUES 
-    ('AEW Forbidden Door 2025', 'All Elite Wrestling', '2025-08-24')
+    ('AEW Forbidden Door 2025', (SELECT id FROM Promotion WHERE name = 'All Elite Wrestling'), '2025-08-24')
   RETURNING id INTO event_id;
   
   INSERT INTO Match (event_id, workers) VALUES
@@ -255,9 +262,9 @@ DECLARE
   event_id INTEGER;
   match_id INTEGER;
 BEGIN
-  INSERT INTO Event (name, promotion, date)
+  INSERT INTO Event (name, promotion_id, date)
   VALUES 
-    ('WWE Wrestlemania 41 - Night 1', 'World Wrestling Entertainment', '2025-04-19')
+    ('WWE Wrestlemania 41 - Night 1', (SELECT id FROM Promotion WHERE name = 'World Wrestling Entertainment'), '2025-04-19')
   RETURNING id INTO event_id;
 
   INSERT INTO Match (event_id, workers) VALUES
@@ -323,9 +330,9 @@ DECLARE
   event_id INTEGER;
   match_id INTEGER;
 BEGIN
-  INSERT INTO Event (name, promotion, date)
+  INSERT INTO Event (name, promotion_id, date)
   VALUES 
-    ('WWE Wrestlemania 41 - Night 2', 'World Wrestling Entertainment', '2025-04-20')
+    ('WWE Wrestlemania 41 - Night 2', (SELECT id FROM Promotion WHERE name = 'World Wrestling Entertainment'), '2025-04-20')
   RETURNING id INTO event_id;
   
   INSERT INTO Match (event_id, workers) VALUES
@@ -390,8 +397,8 @@ DECLARE
   event_id INTEGER;
   match_id INTEGER;
 BEGIN
-  INSERT INTO Event (name, promotion, date) VALUES 
-    ('Stardom All-Star Grand Queendom 2025', 'World Wonder Ring Stardom', '2025-04-27')
+  INSERT INTO Event (name, promotion_id, date) VALUES 
+    ('Stardom All-Star Grand Queendom 2025', (SELECT id FROM Promotion WHERE name = 'World Wonder Ring Stardom'), '2025-04-27')
   RETURNING id INTO event_id;
   
   INSERT INTO Match (event_id, workers) VALUES 
@@ -489,8 +496,8 @@ DECLARE
   event_id INTEGER;
   match_id INTEGER;
 BEGIN
-  INSERT INTO Event (name, promotion, date) VALUES 
-    ('NJPW Wrestle Kingdom 19 In Tokyo Dome', 'New Japan Pro Wrestling', '2025-01-04')
+  INSERT INTO Event (name, promotion_id, date) VALUES 
+    ('NJPW Wrestle Kingdom 19 In Tokyo Dome', (SELECT id FROM Promotion WHERE name = 'New Japan Pro Wrestling'), '2025-01-04')
   RETURNING id INTO event_id;
 
   INSERT INTO Match (event_id, workers) VALUES 
@@ -580,9 +587,9 @@ DECLARE
   event_id INTEGER;
   match_id INTEGER;
 BEGIN
-  INSERT INTO Event (name, promotion, date)
+  INSERT INTO Event (name, promotion_id, date)
   VALUES 
-    ('APC×BZW French Touch', 'Association les Professionnels du Catch, Banger Zone Wrestling', '2025-08-30')
+    ('APC×BZW French Touch', (SELECT id FROM Promotion WHERE name = 'Association les Professionnels du Catch, Banger Zone Wrestling'), '2025-08-30')
   RETURNING id INTO event_id;
 
   INSERT INTO Match (event_id, workers) VALUES